@@ -1,20 +1,77 @@
+mod multiplicative;
+mod pollard_rho;
+mod segmented;
+
+pub use pollard_rho::{factorize_u64, is_prime_u64};
+pub use segmented::{factorize_range, primes_in_range};
+
+use std::{error::Error, fmt};
+
+/// 線形篩で `0..len` の最小素因数の表と素数の列を作り、素因数分解などをします。
+///
+/// 最小素因数の表は `u32` で持つので、メモリは `len` あたり 4 バイト（と素数の列）です。
 #[derive(Debug, Clone)]
 pub struct FastFactrzation {
-    sieve: Vec<Option<usize>>,
+    // `spf[x]` は `x` の最小素因数です。ただし `spf[0] = spf[1] = 0` です。
+    spf: Vec<u32>,
+    primes: Vec<u32>,
 }
 
 impl FastFactrzation {
     pub fn new(len: usize) -> Self {
-        let mut sieve = vec![None; len];
-        for p in (2..).take_while(|&p| p * p < len) {
-            if sieve[p].is_some() {
-                continue;
+        assert!(
+            len <= u32::MAX as usize + 1,
+            "u32 に収まらない数まで篩おうとしています。"
+        );
+        let mut spf = vec![0; len];
+        let mut primes = Vec::new();
+        for i in 2..len {
+            if spf[i] == 0 {
+                spf[i] = i as u32;
+                primes.push(i as u32);
             }
-            for i in seq::step(2 * p, p).take_while(|&i| i < len) {
-                sieve[i] = Some(p);
+            for &p in &primes {
+                let j = i * p as usize;
+                if spf[i] < p || len <= j {
+                    break;
+                }
+                spf[j] = p;
             }
         }
-        Self { sieve }
+        Self { spf, primes }
+    }
+
+    /// 篩った範囲の長さを返します。`0..len()` の数を扱えます。
+    pub fn len(&self) -> usize {
+        self.spf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spf.is_empty()
+    }
+
+    /// `len()` 未満の素数を昇順に返します。
+    pub fn primes(&self) -> &[u32] {
+        &self.primes
+    }
+
+    /// `len()` 未満の素数の個数を返します。
+    pub fn prime_count(&self) -> usize {
+        self.primes.len()
+    }
+
+    pub fn is_prime(&self, x: u32) -> bool {
+        self.assert_in_range(x);
+        x >= 2 && self.spf[x as usize] == x
+    }
+
+    /// 最小素因数を返します。`x` が `0` か `1` のときには `None` です。
+    pub fn smallest_prime_factor(&self, x: u32) -> Option<u32> {
+        self.assert_in_range(x);
+        match self.spf[x as usize] {
+            0 => None,
+            p => Some(p),
+        }
     }
 
     /// 素因数の列を降順で返します。
@@ -22,26 +79,184 @@ impl FastFactrzation {
     pub fn factorize(&self, x: u32) -> Vec<u32> {
         assert_ne!(x, 0, "0 を素因数分解をするのをやめましょう！");
         let mut x = x as usize;
-        if x == 1 {
-            Vec::new()
+        let mut res = Vec::new();
+        while x != 1 {
+            let p = self.spf[x];
+            res.push(p);
+            x /= p as usize;
+        }
+        res.reverse();
+        res
+    }
+
+    /// `factorize` と同じですが、`x` が `0` や `len()` 以上のときにはパニックせずにエラーを返します。
+    pub fn try_factorize(&self, x: u32) -> Result<Vec<u32>, FactorizeError> {
+        self.check(x)?;
+        Ok(self.factorize(x))
+    }
+
+    /// `(素因数, 重複度)` の列を、素因数の昇順で返します。
+    pub fn factorize_pairs(&self, x: u32) -> Vec<(u32, u32)> {
+        self.factors(x).collect()
+    }
+
+    /// `factorize_pairs` と同じですが、`x` が `0` や `len()` 以上のときにはパニックせずにエラーを返します。
+    pub fn try_factorize_pairs(&self, x: u32) -> Result<Vec<(u32, u32)>, FactorizeError> {
+        self.check(x)?;
+        Ok(self.factorize_pairs(x))
+    }
+
+    /// `(素因数, 重複度)` を素因数の昇順に返すイテレータです。アロケーションをしません。
+    pub fn factors(&self, x: u32) -> Factors<'_> {
+        if let Err(e) = self.check(x) {
+            panic!("{}", e);
+        }
+        Factors {
+            spf: &self.spf,
+            x: x as usize,
+        }
+    }
+
+    /// 約数を昇順に返します。
+    pub fn divisors(&self, x: u32) -> Vec<u32> {
+        let mut res = self.divisors_unsorted(x).collect::<Vec<_>>();
+        res.sort_unstable();
+        res
+    }
+
+    /// 約数を順不同に返すイテレータです。アロケーションをしません。
+    pub fn divisors_unsorted(&self, x: u32) -> Divisors {
+        let mut factors = [(0, 0); MAX_DISTINCT_PRIME_FACTORS];
+        let mut len = 0;
+        for (p, e) in self.factors(x) {
+            factors[len] = (p, e);
+            len += 1;
+        }
+        Divisors {
+            factors,
+            len,
+            exponents: [0; MAX_DISTINCT_PRIME_FACTORS],
+            powers: [1; MAX_DISTINCT_PRIME_FACTORS],
+            value: Some(1),
+        }
+    }
+
+    /// 約数の個数を返します。
+    pub fn divisor_count(&self, x: u32) -> u32 {
+        self.factors(x).map(|(_, e)| e + 1).product()
+    }
+
+    /// 約数の `k` 乗の和 σ_k(x) を返します。`k` が大きいとあふれるので注意です。
+    pub fn divisor_sum(&self, x: u32, k: u32) -> u64 {
+        self.factors(x)
+            .map(|(p, e)| (0..=e).map(|i| (p as u64).pow(i * k)).sum::<u64>())
+            .product()
+    }
+
+    fn assert_in_range(&self, x: u32) {
+        assert!(
+            (x as usize) < self.len(),
+            "{} を調べようとしましたが、篩の長さは {} です。",
+            x,
+            self.len()
+        );
+    }
+
+    fn check(&self, x: u32) -> Result<(), FactorizeError> {
+        if x == 0 {
+            Err(FactorizeError::Zero)
+        } else if self.len() <= x as usize {
+            Err(FactorizeError::OutOfRange { x, len: self.len() })
         } else {
-            let mut res = Vec::new();
-            loop {
-                if let Some(p) = self.sieve[x] {
-                    res.push(p as u32);
-                    x /= p;
-                } else {
-                    res.push(x as u32);
-                    return res;
-                }
+            Ok(())
+        }
+    }
+}
+
+/// `FastFactrzation::factors` の返すイテレータです。
+#[derive(Debug, Clone)]
+pub struct Factors<'a> {
+    spf: &'a [u32],
+    x: usize,
+}
+impl Iterator for Factors<'_> {
+    type Item = (u32, u32);
+    fn next(&mut self) -> Option<(u32, u32)> {
+        if self.x == 1 {
+            return None;
+        }
+        let p = self.spf[self.x];
+        let mut e = 0;
+        while self.spf[self.x] == p {
+            self.x /= p as usize;
+            e += 1;
+        }
+        Some((p, e))
+    }
+}
+
+// `u32` の数の相異なる素因数は高々 9 個です。（2 から 29 までの素数の積は `u32` に収まりません。）
+const MAX_DISTINCT_PRIME_FACTORS: usize = 9;
+
+/// `FastFactrzation::divisors_unsorted` の返すイテレータです。
+///
+/// 各素因数の指数を繰り上がりつきで数えていきます。
+#[derive(Debug, Clone)]
+pub struct Divisors {
+    factors: [(u32, u32); MAX_DISTINCT_PRIME_FACTORS],
+    len: usize,
+    exponents: [u32; MAX_DISTINCT_PRIME_FACTORS],
+    // `powers[i]` は `factors[i].0` の `exponents[i]` 乗です。
+    powers: [u32; MAX_DISTINCT_PRIME_FACTORS],
+    value: Option<u32>,
+}
+impl Iterator for Divisors {
+    type Item = u32;
+    fn next(&mut self) -> Option<u32> {
+        let res = self.value?;
+        self.value = None;
+        let mut value = res;
+        for i in 0..self.len {
+            let (p, e) = self.factors[i];
+            if self.exponents[i] < e {
+                self.exponents[i] += 1;
+                self.powers[i] *= p;
+                self.value = Some(value * p);
+                break;
             }
+            value /= self.powers[i];
+            self.exponents[i] = 0;
+            self.powers[i] = 1;
+        }
+        Some(res)
+    }
+}
+
+/// 篩の表で素因数分解できない数を与えたときのエラーです。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FactorizeError {
+    /// `0` は素因数分解できません。
+    Zero,
+    /// `x` が篩った範囲 `0..len` に入っていません。
+    OutOfRange { x: u32, len: usize },
+}
+impl fmt::Display for FactorizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FactorizeError::Zero => write!(f, "0 を素因数分解をするのをやめましょう！"),
+            FactorizeError::OutOfRange { x, len } => write!(
+                f,
+                "{} を素因数分解しようとしましたが、篩の長さは {} です。",
+                x, len
+            ),
         }
     }
 }
+impl Error for FactorizeError {}
 
 #[cfg(test)]
 mod tests {
-    use super::FastFactrzation;
+    use super::{FactorizeError, FastFactrzation};
 
     #[test]
     fn test_hand() {
@@ -59,4 +274,89 @@ mod tests {
         assert_eq!(a.factorize(11), vec![11]);
         assert_eq!(a.factorize(12), vec![3, 2, 2]);
     }
+
+    #[test]
+    fn test_primes() {
+        let len = 2000;
+        let a = FastFactrzation::new(len);
+        let expected = (0..len as u32)
+            .filter(|&x| x >= 2 && (2..x).take_while(|&p| p * p <= x).all(|p| x % p != 0))
+            .collect::<Vec<_>>();
+        assert_eq!(a.primes(), expected.as_slice());
+        assert_eq!(a.prime_count(), expected.len());
+        for x in 0..len as u32 {
+            assert_eq!(a.is_prime(x), expected.binary_search(&x).is_ok());
+        }
+        for x in 1..len as u32 {
+            let result = a.factorize(x);
+            assert!(result.windows(2).all(|v| v[0] >= v[1]));
+            assert!(result.iter().all(|&p| a.is_prime(p)));
+            assert_eq!(result.iter().product::<u32>(), x);
+            assert_eq!(a.smallest_prime_factor(x), result.last().copied());
+        }
+        assert_eq!(FastFactrzation::new(1_000_000).prime_count(), 78498);
+        assert!(FastFactrzation::new(0).primes().is_empty());
+        assert!(FastFactrzation::new(2).primes().is_empty());
+        assert_eq!(FastFactrzation::new(3).primes(), &[2]);
+    }
+
+    #[test]
+    fn test_factorize_pairs() {
+        let len = 2000;
+        let a = FastFactrzation::new(len);
+        for x in 1..len as u32 {
+            let mut expected = Vec::<(u32, u32)>::new();
+            for p in a.factorize(x).into_iter().rev() {
+                match expected.last_mut() {
+                    Some((q, e)) if *q == p => *e += 1,
+                    _ => expected.push((p, 1)),
+                }
+            }
+            assert_eq!(a.factorize_pairs(x), expected);
+            assert_eq!(a.factors(x).collect::<Vec<_>>(), expected);
+            assert_eq!(a.try_factorize_pairs(x), Ok(expected));
+            assert_eq!(a.try_factorize(x), Ok(a.factorize(x)));
+        }
+        assert_eq!(a.factorize_pairs(360), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(a.try_factorize(0), Err(FactorizeError::Zero));
+        assert_eq!(
+            a.try_factorize_pairs(2000),
+            Err(FactorizeError::OutOfRange { x: 2000, len: 2000 })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "10 を調べようとしましたが、篩の長さは 10 です。")]
+    fn test_is_prime_out_of_range() {
+        FastFactrzation::new(10).is_prime(10);
+    }
+
+    #[test]
+    #[should_panic(expected = "10 を調べようとしましたが、篩の長さは 10 です。")]
+    fn test_smallest_prime_factor_out_of_range() {
+        FastFactrzation::new(10).smallest_prime_factor(10);
+    }
+
+    #[test]
+    fn test_divisors() {
+        let len = 3000;
+        let a = FastFactrzation::new(len);
+        for x in 1..len as u32 {
+            let expected = (1..=x).filter(|&d| x % d == 0).collect::<Vec<_>>();
+            assert_eq!(a.divisors(x), expected);
+            let mut result = a.divisors_unsorted(x).collect::<Vec<_>>();
+            result.sort_unstable();
+            assert_eq!(result, expected);
+            assert_eq!(a.divisor_count(x), expected.len() as u32);
+            for k in 0..4 {
+                assert_eq!(
+                    a.divisor_sum(x, k),
+                    expected.iter().map(|&d| (d as u64).pow(k)).sum::<u64>()
+                );
+            }
+        }
+        let a = FastFactrzation::new(30031);
+        assert_eq!(a.divisor_count(30030), 64);
+        assert_eq!(a.divisors_unsorted(30030).count(), 64);
+    }
 }
@@ -0,0 +1,151 @@
+use crate::FastFactrzation;
+
+impl FastFactrzation {
+    /// 素数べきでの値 `f(p, k)` から、`0..len()` での乗法的関数の表を線形時間で作ります。
+    ///
+    /// 互いに素な `a`, `b` について `mul(&f(a), &f(b))` が `f(ab)` になるものとして計算します。
+    /// `mul` を足し算にすれば加法的関数の表も作れます。添字 `0` と `1` の値は `one` です。
+    pub fn multiplicative_table<T: Clone>(
+        &self,
+        one: T,
+        mut f: impl FnMut(u32, u32) -> T,
+        mut mul: impl FnMut(&T, &T) -> T,
+    ) -> Vec<T> {
+        let len = self.len();
+        let mut res = vec![one; len];
+        // `n` を割り切る最大の最小素因数べきと、その指数です。
+        let mut low = vec![0u32; len];
+        let mut exponent = vec![0u8; len];
+        for n in 2..len {
+            let p = self.spf[n];
+            let m = n / p as usize;
+            if self.spf[m] == p {
+                low[n] = low[m] * p;
+                exponent[n] = exponent[m] + 1;
+            } else {
+                low[n] = p;
+                exponent[n] = 1;
+            }
+            let q = low[n] as usize;
+            res[n] = if q == n {
+                f(p, exponent[n] as u32)
+            } else {
+                mul(&res[n / q], &res[q])
+            };
+        }
+        res
+    }
+
+    /// オイラーの φ 関数の表です。`phi[0] = 0` です。
+    pub fn phi_table(&self) -> Vec<u32> {
+        let mut res = self.multiplicative_table(1, |p, k| p.pow(k - 1) * (p - 1), |x, y| x * y);
+        zero_out(&mut res);
+        res
+    }
+
+    /// メビウス関数の表です。`mu[0] = 0` です。
+    pub fn mobius_table(&self) -> Vec<i8> {
+        let mut res =
+            self.multiplicative_table(1, |_, k| if k == 1 { -1 } else { 0 }, |x, y| x * y);
+        zero_out(&mut res);
+        res
+    }
+
+    /// 約数の個数 σ_0 の表です。`sigma_0[0] = 0` です。
+    pub fn sigma0_table(&self) -> Vec<u32> {
+        let mut res = self.multiplicative_table(1, |_, k| k + 1, |x, y| x * y);
+        zero_out(&mut res);
+        res
+    }
+
+    /// 約数の和 σ_1 の表です。`sigma_1[0] = 0` です。
+    pub fn sigma1_table(&self) -> Vec<u64> {
+        let mut res = self.multiplicative_table(
+            1,
+            |p, k| (0..=k).map(|i| (p as u64).pow(i)).sum(),
+            |x, y| x * y,
+        );
+        zero_out(&mut res);
+        res
+    }
+
+    /// 相異なる素因数の個数 ω の表です。
+    pub fn omega_table(&self) -> Vec<u8> {
+        self.multiplicative_table(0, |_, _| 1, |x, y| x + y)
+    }
+
+    /// 重複度こみの素因数の個数 Ω の表です。
+    pub fn big_omega_table(&self) -> Vec<u8> {
+        self.multiplicative_table(0, |_, k| k as u8, |x, y| x + y)
+    }
+
+    /// 相異なる素因数の積（根基）の表です。`rad[0] = 0` です。
+    pub fn radical_table(&self) -> Vec<u32> {
+        let mut res = self.multiplicative_table(1, |p, _| p, |x, y| x * y);
+        zero_out(&mut res);
+        res
+    }
+}
+
+fn zero_out<T: Default>(table: &mut [T]) {
+    if let Some(x) = table.first_mut() {
+        *x = T::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gcd(x: u32, y: u32) -> u32 {
+        if y == 0 {
+            x
+        } else {
+            gcd(y, x % y)
+        }
+    }
+
+    #[test]
+    fn test_tables() {
+        let len = 2000;
+        let a = FastFactrzation::new(len);
+        let phi = a.phi_table();
+        let mu = a.mobius_table();
+        let sigma0 = a.sigma0_table();
+        let sigma1 = a.sigma1_table();
+        let omega = a.omega_table();
+        let big_omega = a.big_omega_table();
+        let radical = a.radical_table();
+        assert_eq!(
+            (
+                phi[0],
+                mu[0],
+                sigma0[0],
+                sigma1[0],
+                omega[0],
+                big_omega[0],
+                radical[0]
+            ),
+            (0, 0, 0, 0, 0, 0, 0)
+        );
+        for n in 1..len as u32 {
+            let i = n as usize;
+            let pairs = a.factorize_pairs(n);
+            let divisors = (1..=n).filter(|&d| n % d == 0).collect::<Vec<_>>();
+            assert_eq!(phi[i], (1..=n).filter(|&x| gcd(x, n) == 1).count() as u32);
+            let expected = if pairs.iter().any(|&(_, e)| e >= 2) {
+                0
+            } else {
+                (-1i8).pow(pairs.len() as u32)
+            };
+            assert_eq!(mu[i], expected);
+            assert_eq!(sigma0[i], divisors.len() as u32);
+            assert_eq!(sigma1[i], divisors.iter().map(|&d| d as u64).sum::<u64>());
+            assert_eq!(omega[i] as usize, pairs.len());
+            assert_eq!(big_omega[i] as usize, a.factorize(n).len());
+            assert_eq!(radical[i], pairs.iter().map(|&(p, _)| p).product::<u32>());
+        }
+        assert!(FastFactrzation::new(0).phi_table().is_empty());
+        assert_eq!(FastFactrzation::new(2).mobius_table(), vec![0, 1]);
+    }
+}
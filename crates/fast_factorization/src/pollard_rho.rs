@@ -0,0 +1,308 @@
+use crate::FastFactrzation;
+
+/// 決定的な Miller–Rabin 法で、`n` が素数かどうかを判定します。
+pub fn is_prime_u64(n: u64) -> bool {
+    if n < 64 {
+        return (1u64 << n) & SMALL_PRIMES_MASK != 0;
+    }
+    if n.is_multiple_of(2) {
+        return false;
+    }
+    let mont = Montgomery::new(n);
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    let one = mont.to_mont(1);
+    let minus_one = mont.to_mont(n - 1);
+    // `u64` 全体で正しく判定できる底の集合です。
+    [2, 325, 9375, 28_178, 450_775, 9_780_504, 1_795_265_022]
+        .iter()
+        .map(|&a| a % n)
+        .filter(|&a| a != 0)
+        .all(|a| {
+            let mut x = mont.pow(mont.to_mont(a), d);
+            if x == one || x == minus_one {
+                return true;
+            }
+            for _ in 1..s {
+                x = mont.mul(x, x);
+                if x == minus_one {
+                    return true;
+                }
+            }
+            false
+        })
+}
+
+/// Pollard–Brent の ρ 法で素因数分解をして、`(素因数, 重複度)` の列を素因数の昇順で返します。
+pub fn factorize_u64(x: u64) -> Vec<(u64, u32)> {
+    factorize_with(x, None)
+}
+
+impl FastFactrzation {
+    /// `factorize_u64` と同じですが、篩の範囲に入る数（途中で現れる約数も含みます）は篩の表で分解します。
+    pub fn factorize_pairs_u64(&self, x: u64) -> Vec<(u64, u32)> {
+        factorize_with(x, Some(self))
+    }
+}
+
+// 64 未満の素数の集合です。
+const SMALL_PRIMES_MASK: u64 = 0x2820_8a20_a08a_28ac;
+
+fn factorize_with(x: u64, sieve: Option<&FastFactrzation>) -> Vec<(u64, u32)> {
+    assert_ne!(x, 0, "0 を素因数分解をするのをやめましょう！");
+    let mut primes = Vec::new();
+    let e = x.trailing_zeros();
+    primes.extend(std::iter::repeat_n(2, e as usize));
+    let mut x = x >> e;
+    // 9 などの小さな合成数は ρ 法では分解できないことがあるので、小さな素因数は先に割っておきます。
+    for p in (3..64).filter(|&p| SMALL_PRIMES_MASK >> p & 1 == 1) {
+        while x.is_multiple_of(p) {
+            primes.push(p);
+            x /= p;
+        }
+    }
+    factorize_odd(x, sieve, &mut primes);
+    primes.sort_unstable();
+    let mut res = Vec::<(u64, u32)>::new();
+    for p in primes {
+        match res.last_mut() {
+            Some((q, e)) if *q == p => *e += 1,
+            _ => res.push((p, 1)),
+        }
+    }
+    res
+}
+
+fn factorize_odd(x: u64, sieve: Option<&FastFactrzation>, primes: &mut Vec<u64>) {
+    if x == 1 {
+        return;
+    }
+    if let Some(sieve) = sieve.filter(|sieve| x < sieve.len() as u64) {
+        primes.extend(
+            sieve
+                .factors(x as u32)
+                .flat_map(|(p, e)| std::iter::repeat_n(p as u64, e as usize)),
+        );
+    } else if is_prime_u64(x) {
+        primes.push(x);
+    } else {
+        let d = find_factor(x);
+        factorize_odd(d, sieve, primes);
+        factorize_odd(x / d, sieve, primes);
+    }
+}
+
+// 奇数の合成数 `n` の、自明でない約数をひとつ返します。
+fn find_factor(n: u64) -> u64 {
+    const BATCH: u64 = 128;
+    let mont = Montgomery::new(n);
+    let one = mont.to_mont(1);
+    for c in 1.. {
+        let c = mont.to_mont(c);
+        let f = |y: u64| mont.add(mont.mul(y, y), c);
+        let mut y = one;
+        let mut x = y;
+        let mut ys = y;
+        let mut q = one;
+        let mut g = 1;
+        let mut r = 1;
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = f(y);
+            }
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys = y;
+                for _ in 0..BATCH.min(r - k) {
+                    y = f(y);
+                    q = mont.mul(q, x.max(y) - x.min(y));
+                }
+                g = gcd(q, n);
+                k += BATCH;
+            }
+            r *= 2;
+        }
+        // まとめて掛けたせいで `n` になってしまったら、一歩ずつやり直します。
+        if g == n {
+            g = 1;
+            while g == 1 {
+                ys = f(ys);
+                g = gcd(x.max(ys) - x.min(ys), n);
+            }
+        }
+        if g != n {
+            return g;
+        }
+    }
+    unreachable!()
+}
+
+fn gcd(mut x: u64, mut y: u64) -> u64 {
+    while y != 0 {
+        x %= y;
+        std::mem::swap(&mut x, &mut y);
+    }
+    x
+}
+
+// 奇数 `n` を法とする Montgomery 乗算です。`R = 2^64` として、`x` を `xR mod n` で表します。
+struct Montgomery {
+    n: u64,
+    // `n * n_inv ≡ 1 (mod R)` です。
+    n_inv: u64,
+    // `R^2 mod n` です。
+    r2: u64,
+}
+impl Montgomery {
+    fn new(n: u64) -> Self {
+        debug_assert!(n % 2 == 1);
+        let mut n_inv = n;
+        for _ in 0..5 {
+            n_inv = n_inv.wrapping_mul(2u64.wrapping_sub(n.wrapping_mul(n_inv)));
+        }
+        let r = ((1u128 << 64) % n as u128) as u64;
+        let r2 = (r as u128 * r as u128 % n as u128) as u64;
+        Self { n, n_inv, r2 }
+    }
+    // `tR^{-1} mod n` を返します。`t < nR` である必要があります。
+    fn reduce(&self, t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(self.n_inv);
+        let mn = ((m as u128 * self.n as u128) >> 64) as u64;
+        let t = (t >> 64) as u64;
+        if mn <= t {
+            t - mn
+        } else {
+            t.wrapping_sub(mn).wrapping_add(self.n)
+        }
+    }
+    fn to_mont(&self, x: u64) -> u64 {
+        self.reduce(x as u128 * self.r2 as u128)
+    }
+    fn add(&self, x: u64, y: u64) -> u64 {
+        let (z, overflow) = x.overflowing_add(y);
+        if overflow || self.n <= z {
+            z.wrapping_sub(self.n)
+        } else {
+            z
+        }
+    }
+    fn mul(&self, x: u64, y: u64) -> u64 {
+        self.reduce(x as u128 * y as u128)
+    }
+    fn pow(&self, mut x: u64, mut e: u64) -> u64 {
+        let mut res = self.to_mont(1);
+        while e != 0 {
+            if e % 2 == 1 {
+                res = self.mul(res, x);
+            }
+            x = self.mul(x, x);
+            e /= 2;
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    fn check(x: u64, result: &[(u64, u32)]) {
+        assert!(result.windows(2).all(|v| v[0].0 < v[1].0), "{:?}", result);
+        assert!(result.iter().all(|&(p, _)| is_prime_u64(p)), "{:?}", result);
+        assert_eq!(
+            result
+                .iter()
+                .map(|&(p, e)| p.pow(e) as u128)
+                .product::<u128>(),
+            x as u128
+        );
+    }
+
+    #[test]
+    fn test_is_prime_u64() {
+        let sieve = FastFactrzation::new(100_000);
+        for x in 0..100_000 {
+            assert_eq!(is_prime_u64(x), sieve.is_prime(x as u32), "x = {}", x);
+        }
+        let primes = [
+            998_244_353,
+            1_000_000_007,
+            (1 << 61) - 1,
+            1_000_000_000_000_000_003,
+            18_446_744_073_709_551_557,
+        ];
+        for &p in &primes {
+            assert!(is_prime_u64(p), "p = {}", p);
+        }
+        let composites = [
+            561,
+            3_215_031_751,
+            3_825_123_056_546_413_051,
+            999_999_937 * 1_000_000_007,
+            4_294_967_291 * 4_294_967_279,
+            u64::MAX,
+        ];
+        for &x in &composites {
+            assert!(!is_prime_u64(x), "x = {}", x);
+        }
+    }
+
+    #[test]
+    fn test_factorize_u64() {
+        assert_eq!(factorize_u64(1), vec![]);
+        assert_eq!(
+            factorize_u64(999_999_937 * 1_000_000_007),
+            vec![(999_999_937, 1), (1_000_000_007, 1)]
+        );
+        assert_eq!(
+            factorize_u64(4_294_967_291 * 4_294_967_279),
+            vec![(4_294_967_279, 1), (4_294_967_291, 1)]
+        );
+        assert_eq!(factorize_u64(1 << 63), vec![(2, 63)]);
+        assert_eq!(
+            factorize_u64(u64::MAX),
+            vec![
+                (3, 1),
+                (5, 1),
+                (17, 1),
+                (257, 1),
+                (641, 1),
+                (65537, 1),
+                (6_700_417, 1)
+            ]
+        );
+        assert_eq!(
+            factorize_u64(1_000_000_007 * 1_000_000_007),
+            vec![(1_000_000_007, 2)]
+        );
+
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let x = rng.gen_range(1, u64::MAX);
+            check(x, &factorize_u64(x));
+            let x = rng.gen_range(1, 1_000_000);
+            check(x, &factorize_u64(x));
+        }
+    }
+
+    #[test]
+    fn test_hybrid() {
+        let sieve = FastFactrzation::new(10_000);
+        for x in 1..10_000 {
+            let expected = sieve
+                .factorize_pairs(x)
+                .into_iter()
+                .map(|(p, e)| (p as u64, e))
+                .collect::<Vec<_>>();
+            assert_eq!(sieve.factorize_pairs_u64(x as u64), expected);
+            assert_eq!(factorize_u64(x as u64), expected);
+        }
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let x = rng.gen_range(1, 1 << 40);
+            assert_eq!(sieve.factorize_pairs_u64(x), factorize_u64(x));
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::ops::Range;
+
+/// 区間 `range` に入る素数を昇順に返します。
+///
+/// `sqrt(end)` 以下の素数を篩ってから区間を篩うので、
+/// メモリは O(sqrt(end) + (end - start)) です。
+pub fn primes_in_range(range: Range<u64>) -> Vec<u64> {
+    let Range { start, end } = range;
+    if end <= start {
+        return Vec::new();
+    }
+    let mut is_prime = vec![true; (end - start) as usize];
+    for x in start..end.min(2) {
+        is_prime[(x - start) as usize] = false;
+    }
+    for p in base_primes(end) {
+        let first = (p * p).max(start.div_ceil(p).saturating_mul(p));
+        if first < end {
+            for i in ((first - start) as usize..is_prime.len()).step_by(p as usize) {
+                is_prime[i] = false;
+            }
+        }
+    }
+    is_prime
+        .iter()
+        .enumerate()
+        .filter(|&(_, &b)| b)
+        .map(|(i, _)| start + i as u64)
+        .collect()
+}
+
+/// 区間 `range` に入る各整数の、`(素因数, 重複度)` の列を素因数の昇順で返します。
+///
+/// `range` は `0` を含んではいけません。メモリは出力を除いて O(sqrt(end) + (end - start)) です。
+pub fn factorize_range(range: Range<u64>) -> Vec<Vec<(u64, u32)>> {
+    let Range { start, end } = range;
+    if end <= start {
+        return Vec::new();
+    }
+    assert_ne!(start, 0, "0 を素因数分解をするのをやめましょう！");
+    let mut rest = (start..end).collect::<Vec<_>>();
+    let mut res = vec![Vec::new(); rest.len()];
+    for p in base_primes(end) {
+        let first = start.div_ceil(p).saturating_mul(p);
+        if first < end {
+            for i in ((first - start) as usize..rest.len()).step_by(p as usize) {
+                let mut e = 0;
+                while rest[i].is_multiple_of(p) {
+                    rest[i] /= p;
+                    e += 1;
+                }
+                res[i].push((p, e));
+            }
+        }
+    }
+    // 残りは `sqrt(end)` より大きな素数です。
+    for (v, &x) in res.iter_mut().zip(&rest) {
+        if x != 1 {
+            v.push((x, 1));
+        }
+    }
+    res
+}
+
+// `p * p < end` なる素数 `p` を昇順に返します。
+fn base_primes(end: u64) -> Vec<u64> {
+    let mut s = ((end - 1) as f64).sqrt() as u64;
+    while s.checked_mul(s).is_none_or(|ss| end - 1 < ss) {
+        s -= 1;
+    }
+    while (s + 1).checked_mul(s + 1).is_some_and(|ss| ss < end) {
+        s += 1;
+    }
+    let len = s as usize + 1;
+    let mut is_prime = vec![true; len];
+    for i in (2..len).take_while(|&i| i * i < len) {
+        if is_prime[i] {
+            for j in (i * i..len).step_by(i) {
+                is_prime[j] = false;
+            }
+        }
+    }
+    (2..len)
+        .filter(|&i| is_prime[i])
+        .map(|i| i as u64)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{factorize_u64, is_prime_u64, FastFactrzation};
+    use rand::prelude::*;
+
+    #[test]
+    fn test_primes_in_range() {
+        let len = 10_000;
+        let sieve = FastFactrzation::new(len);
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let mut start = rng.gen_range(0, len as u64 + 1);
+            let mut end = rng.gen_range(0, len as u64 + 1);
+            if end < start {
+                std::mem::swap(&mut start, &mut end);
+            }
+            let expected = (start..end)
+                .filter(|&x| sieve.is_prime(x as u32))
+                .collect::<Vec<_>>();
+            assert_eq!(primes_in_range(start..end), expected);
+        }
+        assert_eq!(primes_in_range(0..2), vec![]);
+        assert_eq!(primes_in_range(0..3), vec![2]);
+        assert_eq!(primes_in_range(5..5), vec![]);
+
+        for &start in &[1_000_000_000_000, 100_000_000_000_000] {
+            let end = start + 1000;
+            let expected = (start..end)
+                .filter(|&x| is_prime_u64(x))
+                .collect::<Vec<_>>();
+            assert_eq!(primes_in_range(start..end), expected);
+        }
+    }
+
+    #[test]
+    fn test_base_primes() {
+        for end in 1..3000 {
+            let expected = (2..end)
+                .filter(|&p| p * p < end && (2..p).all(|d| p % d != 0))
+                .collect::<Vec<_>>();
+            assert_eq!(base_primes(end), expected, "end = {}", end);
+        }
+    }
+
+    #[test]
+    fn test_factorize_range() {
+        let result = factorize_range(1..3000);
+        for (x, result) in (1..3000).zip(&result) {
+            assert_eq!(result, &factorize_u64(x));
+        }
+        for &start in &[1_000_000_000_000, 100_000_000_000_000] {
+            let end = start + 1000;
+            let result = factorize_range(start..end);
+            assert_eq!(result.len(), 1000);
+            for (x, result) in (start..end).zip(&result) {
+                assert_eq!(result, &factorize_u64(x));
+            }
+        }
+    }
+}
@@ -1,42 +1,96 @@
-use std::iter;
+#[cfg(feature = "rayon")]
+mod par;
+mod range_add;
+mod sparse;
 
+pub use range_add::{RangeAddFenwick2d, SignedInt};
+pub use sparse::SparseFenwick2d;
+
+use std::{
+    fmt, iter,
+    ops::{Range, RangeBounds},
+};
+
+use fenwick_u32::Group;
 use gridtools::exact_size_of_grid;
 
-pub struct Fenwick2d {
-    pub table: Vec<Vec<u32>>,
+#[derive(Clone, PartialEq)]
+pub struct Fenwick2d<T = u32> {
+    table: Vec<Vec<T>>,
 }
-impl Fenwick2d {
+impl<T: Group> Fenwick2d<T> {
     pub fn new() -> Self {
         Self {
-            table: vec![vec![0]],
+            table: vec![vec![T::zero()]],
         }
     }
-    pub fn push(&mut self, i: usize, mut x: u32) {
-        assert!(i < self.table.len());
-        let i = i + 1;
-        if i == 1 {
-            self.table[0].push(0);
+    /// 末尾に行を追加します。`row` の長さは列数と等しい必要があります。
+    pub fn push_row(&mut self, row: &[T]) {
+        let w = self.table[0].len() - 1;
+        assert_eq!(row.len(), w, "行の長さが列数と違います。");
+        let i = self.table.len();
+        let lsb_i = lsb(i);
+        let mut new = iter::once(T::zero())
+            .chain(row.iter().copied())
+            .collect::<Vec<_>>();
+        for j in 1..=w {
+            let next_j = j + lsb(j);
+            if next_j <= w {
+                new[next_j] = new[next_j].add(new[j]);
+            }
         }
-        if i == self.table.len() {
-            self.table.push(vec![0]);
+        for d in iter::successors(Some(1), |&d| Some(2 * d)).take_while(|&d| d != lsb_i) {
+            new.iter_mut()
+                .zip(&self.table[i - d])
+                .for_each(|(x, &y)| *x = x.add(y));
+        }
+        self.table.push(new);
+    }
+    /// 末尾に列を追加します。`column` の長さは行数と等しい必要があります。
+    pub fn push_column(&mut self, column: &[T]) {
+        let h = self.table.len() - 1;
+        assert_eq!(column.len(), h, "列の長さが行数と違います。");
+        let j = self.table[0].len();
+        let lsb_j = lsb(j);
+        let mut new = iter::once(T::zero())
+            .chain(column.iter().copied())
+            .collect::<Vec<_>>();
+        for i in 1..=h {
+            let next_i = i + lsb(i);
+            if next_i <= h {
+                new[next_i] = new[next_i].add(new[i]);
+            }
+        }
+        for (row, x) in self.table.iter_mut().zip(new) {
+            let x = iter::successors(Some(1), |&e| Some(2 * e))
+                .take_while(|&e| e != lsb_j)
+                .map(|e| row[j - e])
+                .fold(x, T::add);
+            row.push(x);
         }
-        let j = self.table[i].len();
-        iter::successors(Some(lsb(i)), |&d| Some(d / 2)).for_each(|d| x += self.table[i - d][j]);
-        iter::successors(Some(lsb(j)), |&e| Some(e / 2)).for_each(|e| x += self.table[i][j - e]);
-        seq::cartesian_product(
-            iter::successors(Some(lsb(i)), |&d| Some(d / 2)),
-            iter::successors(Some(lsb(j)), |&e| Some(e / 2)),
-        )
-        .for_each(|(d, e)| x -= self.table[i - d][j - e]);
-        self.table[i].push(x);
     }
-    pub fn from_slice_vec(src: &[Vec<u32>]) -> Self {
+    /// `h` 行 `w` 列に変更します。増えたセルは `T::zero()` になります。
+    pub fn resize(&mut self, h: usize, w: usize) {
+        let (table_height, table_width) = exact_size_of_grid(&self.table);
+        if w + 1 < table_width {
+            self.table.iter_mut().for_each(|row| row.truncate(w + 1));
+        }
+        for _ in table_width..w + 1 {
+            self.push_column(&vec![T::zero(); table_height - 1]);
+        }
+        self.table.truncate(h + 1);
+        for _ in table_height..h + 1 {
+            self.push_row(&vec![T::zero(); w]);
+        }
+    }
+    pub fn from_slice_vec(src: &[Vec<T>]) -> Self {
         let (h, w) = exact_size_of_grid(src);
-        let mut table = iter::once(vec![0; w + 1])
-            .chain(
-                src.iter()
-                    .map(|v| iter::once(0).chain(v.iter().copied()).collect::<Vec<_>>()),
-            )
+        let mut table = iter::once(vec![T::zero(); w + 1])
+            .chain(src.iter().map(|v| {
+                iter::once(T::zero())
+                    .chain(v.iter().copied())
+                    .collect::<Vec<_>>()
+            }))
             .collect::<Vec<_>>();
         for ((i, next_i), j) in seq::cartesian_product(
             (1..=h)
@@ -45,7 +99,7 @@ impl Fenwick2d {
             1..=w,
         ) {
             let x = table[i][j];
-            table[next_i][j] += x
+            table[next_i][j] = table[next_i][j].add(x);
         }
         for ((j, next_j), i) in seq::cartesian_product(
             (1..=w)
@@ -54,36 +108,104 @@ impl Fenwick2d {
             1..=h,
         ) {
             let x = table[i][j];
-            table[i][next_j] += x
+            table[i][next_j] = table[i][next_j].add(x);
         }
         Self { table }
     }
-    pub fn double_prefix_sum(&self, i: usize, j: usize) -> u32 {
+    /// `(行数, 列数)` を返します。
+    pub fn dims(&self) -> (usize, usize) {
+        (self.table.len() - 1, self.table[0].len() - 1)
+    }
+    /// 元の値の表を O(hw) で復元します。
+    pub fn to_grid(&self) -> Vec<Vec<T>> {
+        let mut table = self.table.clone();
+        let (h, w) = self.dims();
+        for ((j, next_j), i) in seq::cartesian_product(
+            (1..=w)
+                .rev()
+                .map(|j| (j, j + lsb(j)))
+                .filter(|&(_, next_j)| next_j <= w),
+            1..=h,
+        ) {
+            let x = table[i][j];
+            table[i][next_j] = table[i][next_j].sub(x);
+        }
+        for ((i, next_i), j) in seq::cartesian_product(
+            (1..=h)
+                .rev()
+                .map(|i| (i, i + lsb(i)))
+                .filter(|&(_, next_i)| next_i <= h),
+            1..=w,
+        ) {
+            let x = table[i][j];
+            table[next_i][j] = table[next_i][j].sub(x);
+        }
+        table
+            .into_iter()
+            .skip(1)
+            .map(|row| row.into_iter().skip(1).collect())
+            .collect()
+    }
+    pub fn double_prefix_sum(&self, i: usize, j: usize) -> T {
         seq::cartesian_product(
             iter::successors(Some(i), |&i| Some(i - lsb(i))).take_while(|&i| i != 0),
             iter::successors(Some(j), |&j| Some(j - lsb(j))).take_while(|&j| j != 0),
         )
         .map(|(i, j)| self.table[i][j])
-        .sum()
+        .fold(T::zero(), T::add)
+    }
+    /// 長方形領域の和を返します。
+    ///
+    /// 整数型の `Group` の演算は wrapping なので、途中の値が負になったり型に収まらなくなったり
+    /// しても、答えが型に収まっていれば正しく計算できます。
+    pub fn rect_sum(&self, rows: impl RangeBounds<usize>, cols: impl RangeBounds<usize>) -> T {
+        let (h, w) = exact_size_of_grid(&self.table);
+        let Range { start: i0, end: i1 } = open(h - 1, rows);
+        let Range { start: j0, end: j1 } = open(w - 1, cols);
+        assert!(i0 <= i1 && j0 <= j1);
+        self.double_prefix_sum(i1, j1)
+            .sub(self.double_prefix_sum(i0, j1))
+            .sub(self.double_prefix_sum(i1, j0))
+            .add(self.double_prefix_sum(i0, j0))
+    }
+    pub fn get(&self, i: usize, j: usize) -> T {
+        self.rect_sum(i..=i, j..=j)
+    }
+    pub fn set(&mut self, i: usize, j: usize, x: T) {
+        let y = self.get(i, j);
+        self.add(i, j, x.sub(y));
+    }
+    pub fn add(&mut self, i: usize, j: usize, x: T) {
+        let (h, w) = exact_size_of_grid(&self.table);
+        seq::cartesian_product(
+            iter::successors(Some(i + 1), |&i| Some(i + lsb(i))).take_while(|&i| i < h),
+            iter::successors(Some(j + 1), |&j| Some(j + lsb(j))).take_while(|&j| j < w),
+        )
+        .for_each(|(i, j)| self.table[i][j] = self.table[i][j].add(x));
     }
-    pub fn add(&mut self, i: usize, j: usize, x: u32) {
+    pub fn sub(&mut self, i: usize, j: usize, x: T) {
         let (h, w) = exact_size_of_grid(&self.table);
         seq::cartesian_product(
             iter::successors(Some(i + 1), |&i| Some(i + lsb(i))).take_while(|&i| i < h),
             iter::successors(Some(j + 1), |&j| Some(j + lsb(j))).take_while(|&j| j < w),
         )
-        .for_each(|(i, j)| self.table[i][j] += x);
+        .for_each(|(i, j)| self.table[i][j] = self.table[i][j].sub(x));
     }
-    pub fn horizontal_upper_bound(&self, i: usize, x: &u32) -> usize {
+    /// `pred(&double_prefix_sum(i, j))` が `true` となる最大の `j` を返します。
+    ///
+    /// `pred` は `j` について単調（あるところまで `true` で、そこから先は `false`）で、
+    /// `pred(&T::zero())` は `true` である必要があります。
+    /// `false` になる最小の `j`、つまり条件を満たさなくなる最小の prefix は、返り値に 1 を足したものです。
+    pub fn horizontal_descend(&self, i: usize, mut pred: impl FnMut(&T) -> bool) -> usize {
         let table_width = exact_size_of_grid(&self.table).1;
         let mut j = 0;
-        let mut now = 0;
+        let mut now = T::zero();
         for d in iter::successors(Some(table_width.next_power_of_two() / 2), |&d| Some(d / 2))
             .take_while(|&d| d != 0)
         {
             if j + d < table_width {
-                let next = now + self.i_prefix_sum_j_raw_element(i, j + d);
-                if &next <= x {
+                let next = now.add(self.i_prefix_sum_j_raw_element(i, j + d));
+                if pred(&next) {
                     j += d;
                     now = next;
                 }
@@ -91,13 +213,91 @@ impl Fenwick2d {
         }
         j
     }
-    fn i_prefix_sum_j_raw_element(&self, i: usize, j: usize) -> u32 {
+    /// `pred(&double_prefix_sum(i, j))` が `true` となる最大の `i` を返します。
+    ///
+    /// `pred` についての条件は `horizontal_descend` と同じです。
+    pub fn vertical_descend(&self, j: usize, mut pred: impl FnMut(&T) -> bool) -> usize {
+        let table_height = self.table.len();
+        let mut i = 0;
+        let mut now = T::zero();
+        for d in iter::successors(Some(table_height.next_power_of_two() / 2), |&d| Some(d / 2))
+            .take_while(|&d| d != 0)
+        {
+            if i + d < table_height {
+                let next = now.add(self.raw_element_i_j_prefix_sum(i + d, j));
+                if pred(&next) {
+                    i += d;
+                    now = next;
+                }
+            }
+        }
+        i
+    }
+    fn i_prefix_sum_j_raw_element(&self, i: usize, j: usize) -> T {
         iter::successors(Some(i), |&i| Some(i - lsb(i)))
             .take_while(|&j| j != 0)
             .map(|i| self.table[i][j])
-            .sum()
+            .fold(T::zero(), T::add)
+    }
+    fn raw_element_i_j_prefix_sum(&self, i: usize, j: usize) -> T {
+        iter::successors(Some(j), |&j| Some(j - lsb(j)))
+            .take_while(|&j| j != 0)
+            .map(|j| self.table[i][j])
+            .fold(T::zero(), T::add)
+    }
+}
+impl<T: Group + Send + Sync> Fenwick2d<T> {
+    /// `double_prefix_sum` をまとめて計算します。`rayon` feature が有効ならば並列に計算します。
+    pub fn double_prefix_sums(&self, indices: &[(usize, usize)]) -> Vec<T> {
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            indices
+                .par_iter()
+                .map(|&(i, j)| self.double_prefix_sum(i, j))
+                .collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            indices
+                .iter()
+                .map(|&(i, j)| self.double_prefix_sum(i, j))
+                .collect()
+        }
+    }
+}
+impl<T: Group + Ord> Fenwick2d<T> {
+    /// `double_prefix_sum(i, j) <= x` となる最大の `j` を返します。
+    pub fn horizontal_upper_bound(&self, i: usize, x: &T) -> usize {
+        self.horizontal_descend(i, |now| now <= x)
+    }
+    /// `double_prefix_sum(i, j) <= x` となる最大の `i` を返します。
+    pub fn vertical_upper_bound(&self, j: usize, x: &T) -> usize {
+        self.vertical_descend(j, |now| now <= x)
+    }
+}
+impl<T: Group> Default for Fenwick2d<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: Group + fmt::Debug> fmt::Debug for Fenwick2d<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", dbg::Tabular(&self.to_grid()))
     }
 }
+fn open(len: usize, range: impl RangeBounds<usize>) -> Range<usize> {
+    use std::ops::Bound::*;
+    (match range.start_bound() {
+        Unbounded => 0,
+        Included(&x) => x,
+        Excluded(&x) => x + 1,
+    })..(match range.end_bound() {
+        Excluded(&x) => x,
+        Included(&x) => x + 1,
+        Unbounded => len,
+    })
+}
 #[inline]
 fn lsb(i: usize) -> usize {
     i & i.wrapping_neg()
@@ -108,7 +308,7 @@ mod tests {
     use super::*;
     use rand::prelude::*;
     use span::Span;
-    use std::iter;
+    use std::{iter, ops::Bound};
 
     const TEST_COUNT: usize = 20;
     const QUERY_COUNT: usize = 2000;
@@ -123,6 +323,28 @@ mod tests {
         rng.gen_range(0, n)
     }
 
+    fn gen_range_bounds(
+        rng: &mut StdRng,
+        n: usize,
+    ) -> (Range<usize>, (Bound<usize>, Bound<usize>)) {
+        let mut l = rng.gen_range(0, n + 1);
+        let mut r = rng.gen_range(0, n + 1);
+        if r < l {
+            std::mem::swap(&mut l, &mut r);
+        }
+        let start = match rng.gen_range(0, 3) {
+            0 if l == 0 => Bound::Unbounded,
+            1 if l != 0 => Bound::Excluded(l - 1),
+            _ => Bound::Included(l),
+        };
+        let end = match rng.gen_range(0, 3) {
+            0 if r == n => Bound::Unbounded,
+            1 if r != 0 => Bound::Included(r - 1),
+            _ => Bound::Excluded(r),
+        };
+        (l..r, (start, end))
+    }
+
     #[test]
     fn test_hand() {
         let mut rng: StdRng = SeedableRng::seed_from_u64(42);
@@ -144,14 +366,14 @@ mod tests {
             println!(
                 "CREATED AN INSTANCE:\ntable:\n{:?}fenwick:\n{:?}",
                 dbg::Tabular(&table),
-                dbg::Tabular(&fenwick.table)
+                &fenwick
             );
 
             for _ in 0..QUERY_COUNT {
                 let (h, w) = exact_size_of_grid(&table);
                 match rng.gen_range(0, 100) {
                     // double prefix_sum
-                    0..=39 => {
+                    0..=29 => {
                         let i = gen_index(&mut rng, h);
                         let j = gen_index(&mut rng, w);
                         let expected = table[..i]
@@ -167,7 +389,7 @@ mod tests {
                         assert_eq!(expected, result);
                     }
                     // add
-                    40..=79 => {
+                    30..=59 => {
                         let i = gen_index(&mut rng, h);
                         let j = gen_index(&mut rng, w);
                         let x = gen_value(&mut rng);
@@ -176,7 +398,7 @@ mod tests {
                         fenwick.add(i, j, x);
                     }
                     // horizontal_upper_bound
-                    80..=99 => {
+                    60..=67 => {
                         let i = gen_index(&mut rng, h);
                         let x = rng.gen_range(
                             VALUE_MININUM * (h * w / 16) as u32,
@@ -197,9 +419,293 @@ mod tests {
                         println!("Horizontal upper bound ( i = {}, x = {} ) -> ( expected = {}, result = {} )", i, x, expected, result);
                         assert_eq!(expected, result);
                     }
+                    // vertical_upper_bound
+                    68..=74 => {
+                        let j = gen_index(&mut rng, w);
+                        let x = rng.gen_range(
+                            VALUE_MININUM * (h * w / 16) as u32,
+                            VALUE_LIMIT * (h * w / 16) as u32,
+                        );
+                        let mut b = vec![0; h + 1];
+                        for (i, v) in table.iter().enumerate() {
+                            b[i + 1] = b[i] + v[..j].iter().sum::<u32>();
+                        }
+                        let expected = b.upper_bound(&x) - 1;
+                        let result = fenwick.vertical_upper_bound(j, &x);
+                        println!("Vertical upper bound ( j = {}, x = {} ) -> ( expected = {}, result = {} )", j, x, expected, result);
+                        assert_eq!(expected, result);
+                    }
+                    // rect_sum
+                    75..=89 => {
+                        let (rows, rows_bounds) = gen_range_bounds(&mut rng, h);
+                        let (cols, cols_bounds) = gen_range_bounds(&mut rng, w);
+                        let expected = table[rows.clone()]
+                            .iter()
+                            .flat_map(|row| row[cols.clone()].iter())
+                            .sum::<u32>();
+                        let result = fenwick.rect_sum(rows_bounds, cols_bounds);
+                        println!(
+                            "Rect sum ( rows = {:?}, cols = {:?} ) -> ( expected = {}, result = {} )",
+                            rows_bounds, cols_bounds, expected, result
+                        );
+                        assert_eq!(expected, result);
+                    }
+                    // get
+                    90..=94 => {
+                        let i = gen_index(&mut rng, h);
+                        let j = gen_index(&mut rng, w);
+                        let expected = table[i][j];
+                        let result = fenwick.get(i, j);
+                        println!(
+                            "Get ( i = {}, j = {} ) -> ( expected = {}, result = {} )",
+                            i, j, expected, result
+                        );
+                        assert_eq!(expected, result);
+                    }
+                    // set
+                    95..=99 => {
+                        let i = gen_index(&mut rng, h);
+                        let j = gen_index(&mut rng, w);
+                        let x = gen_value(&mut rng);
+                        println!("Set ( i = {}, j = {}, x = {})", i, j, x);
+                        table[i][j] = x;
+                        fenwick.set(i, j, x);
+                    }
                     100..=std::u32::MAX => unreachable!(),
                 }
             }
         }
     }
+
+    #[test]
+    fn test_growth() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let mut table = Vec::<Vec<u32>>::new();
+            let mut w = 0;
+            let mut fenwick = Fenwick2d::new();
+
+            for _ in 0..QUERY_COUNT / 4 {
+                let h = table.len();
+                match rng.gen_range(0, 8) {
+                    // push_row
+                    0 if h < 20 => {
+                        let row = iter::repeat_with(|| gen_value(&mut rng))
+                            .take(w)
+                            .collect::<Vec<_>>();
+                        fenwick.push_row(&row);
+                        table.push(row);
+                    }
+                    // push_column
+                    1 if w < 20 => {
+                        let column = iter::repeat_with(|| gen_value(&mut rng))
+                            .take(h)
+                            .collect::<Vec<_>>();
+                        fenwick.push_column(&column);
+                        table
+                            .iter_mut()
+                            .zip(&column)
+                            .for_each(|(row, &x)| row.push(x));
+                        w += 1;
+                    }
+                    // resize
+                    2 => {
+                        let new_h = rng.gen_range(0, 20);
+                        w = rng.gen_range(0, 20);
+                        fenwick.resize(new_h, w);
+                        table.resize(new_h, Vec::new());
+                        table.iter_mut().for_each(|row| row.resize(w, 0));
+                    }
+                    // add
+                    3 | 4 if h != 0 && w != 0 => {
+                        let i = gen_index(&mut rng, h);
+                        let j = gen_index(&mut rng, w);
+                        let x = gen_value(&mut rng);
+                        table[i][j] += x;
+                        fenwick.add(i, j, x);
+                    }
+                    // rect_sum
+                    _ => {
+                        let (rows, _) = gen_range_bounds(&mut rng, h);
+                        let (cols, _) = gen_range_bounds(&mut rng, w);
+                        let expected = table[rows.clone()]
+                            .iter()
+                            .flat_map(|row| row[cols.clone()].iter())
+                            .sum::<u32>();
+                        assert_eq!(fenwick.rect_sum(rows, cols), expected);
+                    }
+                }
+                assert_eq!(fenwick.dims(), (table.len(), w));
+                if !table.is_empty() {
+                    assert_eq!(fenwick, Fenwick2d::from_slice_vec(&table));
+                }
+                assert_eq!(fenwick.to_grid(), table);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rect_sum_does_not_overflow() {
+        let big: u32 = 3_000_000_000;
+        let mut table = vec![vec![0; 3]; 3];
+        table[0][0] = big;
+        table[2][2] = big;
+        let mut fenwick = Fenwick2d::from_slice_vec(&table);
+        assert_eq!(fenwick.rect_sum(2.., 2..), big);
+        assert_eq!(fenwick.rect_sum(1.., 1..), big);
+        assert_eq!(fenwick.rect_sum(..2, ..2), big);
+        assert_eq!(fenwick.rect_sum(1..2, ..), 0);
+        assert_eq!(fenwick.get(2, 2), big);
+        fenwick.set(2, 2, 1);
+        assert_eq!(fenwick.rect_sum(.., ..), big + 1);
+    }
+
+    #[test]
+    fn test_signed() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let h = rng.gen_range(1, 20);
+            let w = rng.gen_range(1, 20);
+            let mut table = iter::repeat_with(|| {
+                iter::repeat_with(|| rng.gen_range(-1_000_000_000_000, 1_000_000_000_000))
+                    .take(w)
+                    .collect::<Vec<i64>>()
+            })
+            .take(h)
+            .collect::<Vec<_>>();
+            let mut fenwick = Fenwick2d::from_slice_vec(&table);
+
+            for _ in 0..QUERY_COUNT {
+                let i = gen_index(&mut rng, h);
+                let j = gen_index(&mut rng, w);
+                match rng.gen_range(0, 3) {
+                    0 => {
+                        let x = rng.gen_range(-1_000_000_000_000, 1_000_000_000_000);
+                        table[i][j] += x;
+                        fenwick.add(i, j, x);
+                    }
+                    1 => {
+                        let x = rng.gen_range(-1_000_000_000_000, 1_000_000_000_000);
+                        table[i][j] = x;
+                        fenwick.set(i, j, x);
+                    }
+                    2 => {
+                        let (rows, _) = gen_range_bounds(&mut rng, h);
+                        let (cols, _) = gen_range_bounds(&mut rng, w);
+                        let expected = table[rows.clone()]
+                            .iter()
+                            .flat_map(|row| row[cols.clone()].iter())
+                            .sum::<i64>();
+                        assert_eq!(fenwick.rect_sum(rows, cols), expected);
+                    }
+                    _ => unreachable!(),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_user_defined_group() {
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        struct Mod7(u32);
+        impl Group for Mod7 {
+            fn zero() -> Self {
+                Mod7(0)
+            }
+            fn add(self, rhs: Self) -> Self {
+                Mod7((self.0 + rhs.0) % 7)
+            }
+            fn sub(self, rhs: Self) -> Self {
+                Mod7((self.0 + 7 - rhs.0) % 7)
+            }
+        }
+
+        let table = (0..5)
+            .map(|i| (0..6).map(|j| Mod7((i * 6 + j) % 7)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let mut fenwick = Fenwick2d::from_slice_vec(&table);
+        assert_eq!(fenwick.rect_sum(.., ..), Mod7((0..30).sum::<u32>() % 7));
+        assert_eq!(
+            fenwick.rect_sum(1..3, 2..5),
+            Mod7((8 + 9 + 10 + 14 + 15 + 16) % 7)
+        );
+        fenwick.set(2, 3, Mod7(6));
+        assert_eq!(fenwick.get(2, 3), Mod7(6));
+        assert_eq!(
+            fenwick.rect_sum(1..3, 2..5),
+            Mod7((8 + 9 + 10 + 14 + 6 + 16) % 7)
+        );
+    }
+
+    #[test]
+    fn test_fp() {
+        type Fp = fp::F998244353;
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let h = rng.gen_range(1, 10);
+            let w = rng.gen_range(1, 10);
+            let mut table = iter::repeat_with(|| {
+                iter::repeat_with(|| Fp::new(rng.gen_range(0, 998_244_353)))
+                    .take(w)
+                    .collect::<Vec<_>>()
+            })
+            .take(h)
+            .collect::<Vec<_>>();
+            let mut fenwick = Fenwick2d::from_slice_vec(&table);
+
+            for _ in 0..QUERY_COUNT / 10 {
+                let i = rng.gen_range(0, h);
+                let j = rng.gen_range(0, w);
+                let x = Fp::new(rng.gen_range(0, 998_244_353));
+                table[i][j] -= x;
+                fenwick.sub(i, j, x);
+                let i = rng.gen_range(0, h + 1);
+                let j = rng.gen_range(0, w + 1);
+                let expected = table[..i]
+                    .iter()
+                    .flat_map(|row| row[..j].iter())
+                    .copied()
+                    .sum::<Fp>();
+                assert_eq!(fenwick.double_prefix_sum(i, j), expected);
+            }
+            assert_eq!(fenwick.to_grid(), table);
+        }
+    }
+
+    #[test]
+    fn test_descend() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let h = rng.gen_range(1, 20);
+            let w = rng.gen_range(1, 20);
+            let table = iter::repeat_with(|| {
+                iter::repeat_with(|| gen_value(&mut rng))
+                    .take(w)
+                    .collect::<Vec<_>>()
+            })
+            .take(h)
+            .collect::<Vec<_>>();
+            let fenwick = Fenwick2d::from_slice_vec(&table);
+            let brute = |i: usize, j: usize| {
+                table[..i]
+                    .iter()
+                    .flat_map(|row| row[..j].iter())
+                    .sum::<u32>()
+            };
+
+            for _ in 0..QUERY_COUNT / 10 {
+                let target = rng.gen_range(0, VALUE_LIMIT * (h * w) as u32 / 2 + 2);
+                let i = rng.gen_range(0, h + 1);
+                let expected = (0..=w).rev().find(|&j| brute(i, j) < target).unwrap_or(0);
+                assert_eq!(fenwick.horizontal_descend(i, |&s| s < target), expected);
+                let j = rng.gen_range(0, w + 1);
+                let expected = (0..=h).rev().find(|&i| brute(i, j) < target).unwrap_or(0);
+                assert_eq!(fenwick.vertical_descend(j, |&s| s < target), expected);
+            }
+        }
+    }
 }
@@ -0,0 +1,87 @@
+use crate::{lsb, Fenwick2d};
+use fenwick_u32::Group;
+use gridtools::exact_size_of_grid;
+use rayon::prelude::*;
+use std::iter;
+
+impl<T: Group + Send + Sync> Fenwick2d<T> {
+    /// `from_slice_vec` と同じ表を並列に構築します。
+    pub fn from_slice_vec_par(src: &[Vec<T>]) -> Self {
+        let (h, w) = exact_size_of_grid(src);
+        let mut table = iter::once(vec![T::zero(); w + 1])
+            .chain(src.iter().map(|v| {
+                iter::once(T::zero())
+                    .chain(v.iter().copied())
+                    .collect::<Vec<_>>()
+            }))
+            .collect::<Vec<_>>();
+        table[1..].par_iter_mut().for_each(|row| {
+            for j in 1..=w {
+                let next_j = j + lsb(j);
+                if next_j <= w {
+                    row[next_j] = row[next_j].add(row[j]);
+                }
+            }
+        });
+        for i in 1..=h {
+            let next_i = i + lsb(i);
+            if next_i <= h {
+                let (lower, upper) = table.split_at_mut(next_i);
+                upper[0]
+                    .par_iter_mut()
+                    .zip(lower[i].par_iter())
+                    .for_each(|(x, &y)| *x = x.add(y));
+            }
+        }
+        Self { table }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn test_from_slice_vec_par() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..100 {
+            let h = rng.gen_range(0, 40);
+            let w = rng.gen_range(0, 40);
+            let a = iter::repeat_with(|| {
+                iter::repeat_with(|| rng.gen_range(0, 10))
+                    .take(w)
+                    .collect::<Vec<u32>>()
+            })
+            .take(h)
+            .collect::<Vec<_>>();
+            assert_eq!(
+                Fenwick2d::from_slice_vec_par(&a),
+                Fenwick2d::from_slice_vec(&a)
+            );
+        }
+    }
+
+    #[test]
+    fn test_double_prefix_sums() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+        let (h, w) = (30, 40);
+        let a = iter::repeat_with(|| {
+            iter::repeat_with(|| rng.gen_range(0, 10))
+                .take(w)
+                .collect::<Vec<u32>>()
+        })
+        .take(h)
+        .collect::<Vec<_>>();
+        let fenwick = Fenwick2d::from_slice_vec_par(&a);
+        let indices = iter::repeat_with(|| (rng.gen_range(0, h + 1), rng.gen_range(0, w + 1)))
+            .take(1000)
+            .collect::<Vec<_>>();
+        let expected = indices
+            .iter()
+            .map(|&(i, j)| a[..i].iter().flat_map(|row| &row[..j]).sum::<u32>())
+            .collect::<Vec<_>>();
+        assert_eq!(fenwick.double_prefix_sums(&indices), expected);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{open, Fenwick2d};
+use fenwick_u32::Group;
+use std::ops::{Range, RangeBounds};
+
+/// `RangeAddFenwick2d` の値の型に使う、符号付き整数型です。
+pub trait SignedInt: Group {
+    /// 添字 `i` 倍します。`Group` の演算と同じく wrapping です。
+    fn mul_index(self, i: usize) -> Self;
+}
+macro_rules! impl_signed_int {
+    ($($t:ty),*) => {$(
+        impl SignedInt for $t {
+            fn mul_index(self, i: usize) -> Self {
+                self.wrapping_mul(i as $t)
+            }
+        }
+    )*};
+}
+impl_signed_int!(i8, i16, i32, i64, i128, isize);
+
+/// 長方形領域への一様な加算と、長方形領域の和の取得ができる 2 次元 Fenwick 木です。
+///
+/// 差分の表 `d` に対して `(i, j)` までの和は `Σ d[p][q] (i - p) (j - q)` なので、
+/// これを展開した 4 つの項 `d`, `d q`, `d p`, `d p q` をそれぞれ `Fenwick2d` で持ちます。
+pub struct RangeAddFenwick2d<T> {
+    h: usize,
+    w: usize,
+    tables: [Fenwick2d<T>; 4],
+}
+impl<T: SignedInt> RangeAddFenwick2d<T> {
+    /// すべて `0` の `h` 行 `w` 列の表を作ります。
+    pub fn new(h: usize, w: usize) -> Self {
+        let zero = vec![vec![T::zero(); w]; h];
+        Self {
+            h,
+            w,
+            tables: [
+                Fenwick2d::from_slice_vec(&zero),
+                Fenwick2d::from_slice_vec(&zero),
+                Fenwick2d::from_slice_vec(&zero),
+                Fenwick2d::from_slice_vec(&zero),
+            ],
+        }
+    }
+    /// 長方形領域のすべてのセルに `x` を足します。
+    pub fn add_rect(&mut self, rows: impl RangeBounds<usize>, cols: impl RangeBounds<usize>, x: T) {
+        let Range { start: i0, end: i1 } = open(self.h, rows);
+        let Range { start: j0, end: j1 } = open(self.w, cols);
+        assert!(i0 <= i1 && i1 <= self.h && j0 <= j1 && j1 <= self.w);
+        self.add_corner(i0, j0, x);
+        self.add_corner(i1, j0, T::zero().sub(x));
+        self.add_corner(i0, j1, T::zero().sub(x));
+        self.add_corner(i1, j1, x);
+    }
+    /// 長方形領域の和を返します。
+    pub fn rect_sum(&self, rows: impl RangeBounds<usize>, cols: impl RangeBounds<usize>) -> T {
+        let Range { start: i0, end: i1 } = open(self.h, rows);
+        let Range { start: j0, end: j1 } = open(self.w, cols);
+        assert!(i0 <= i1 && i1 <= self.h && j0 <= j1 && j1 <= self.w);
+        self.double_prefix_sum(i1, j1)
+            .sub(self.double_prefix_sum(i0, j1))
+            .sub(self.double_prefix_sum(i1, j0))
+            .add(self.double_prefix_sum(i0, j0))
+    }
+    pub fn double_prefix_sum(&self, i: usize, j: usize) -> T {
+        let [t0, t1, t2, t3] = &self.tables;
+        t0.double_prefix_sum(i, j)
+            .mul_index(i)
+            .mul_index(j)
+            .sub(t1.double_prefix_sum(i, j).mul_index(i))
+            .sub(t2.double_prefix_sum(i, j).mul_index(j))
+            .add(t3.double_prefix_sum(i, j))
+    }
+    // 差分の表の `(i, j)` に `x` を足します。表の外（`i == h` や `j == w`）は何もしません。
+    fn add_corner(&mut self, i: usize, j: usize, x: T) {
+        let [t0, t1, t2, t3] = &mut self.tables;
+        t0.add(i, j, x);
+        t1.add(i, j, x.mul_index(j));
+        t2.add(i, j, x.mul_index(i));
+        t3.add(i, j, x.mul_index(i).mul_index(j));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+    use std::iter;
+
+    const TEST_COUNT: usize = 20;
+    const QUERY_COUNT: usize = 1000;
+
+    fn gen_range(rng: &mut StdRng, n: usize) -> Range<usize> {
+        let mut l = rng.gen_range(0, n + 1);
+        let mut r = rng.gen_range(0, n + 1);
+        if r < l {
+            std::mem::swap(&mut l, &mut r);
+        }
+        l..r
+    }
+
+    #[test]
+    fn test_range_add() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let h = rng.gen_range(0, 15);
+            let w = rng.gen_range(0, 15);
+            let mut fenwick = RangeAddFenwick2d::<i64>::new(h, w);
+            let mut brute = vec![vec![0; w]; h];
+
+            for _ in 0..QUERY_COUNT {
+                let rows = gen_range(&mut rng, h);
+                let cols = gen_range(&mut rng, w);
+                match rng.gen_range(0, 2) {
+                    0 => {
+                        let x = rng.gen_range(-1_000_000_000, 1_000_000_000);
+                        brute[rows.clone()]
+                            .iter_mut()
+                            .flat_map(|row| row[cols.clone()].iter_mut())
+                            .for_each(|y| *y += x);
+                        fenwick.add_rect(rows, cols, x);
+                    }
+                    1 => {
+                        let expected = brute[rows.clone()]
+                            .iter()
+                            .flat_map(|row| row[cols.clone()].iter())
+                            .sum::<i64>();
+                        assert_eq!(fenwick.rect_sum(rows, cols), expected);
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            let expected = brute.iter().flatten().sum::<i64>();
+            assert_eq!(fenwick.rect_sum(.., ..), expected);
+        }
+    }
+
+    #[test]
+    fn test_small_types() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let h = rng.gen_range(1, 10);
+            let w = rng.gen_range(1, 10);
+            let mut fenwick = RangeAddFenwick2d::<i16>::new(h, w);
+            let mut brute = vec![vec![0i16; w]; h];
+
+            for _ in 0..QUERY_COUNT / 10 {
+                let rows = gen_range(&mut rng, h);
+                let cols = gen_range(&mut rng, w);
+                let x = rng.gen_range(-3, 4);
+                brute[rows.clone()]
+                    .iter_mut()
+                    .flat_map(|row| row[cols.clone()].iter_mut())
+                    .for_each(|y| *y += x);
+                fenwick.add_rect(rows, cols, x);
+            }
+            for (i, j) in iter::repeat_with(|| (rng.gen_range(0, h), rng.gen_range(0, w))).take(20)
+            {
+                assert_eq!(fenwick.rect_sum(i..=i, j..=j), brute[i][j]);
+            }
+        }
+    }
+}
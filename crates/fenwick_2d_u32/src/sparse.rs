@@ -0,0 +1,176 @@
+use crate::lsb;
+use fenwick_u32::{Fenwick, Group};
+use std::{
+    fmt, iter,
+    ops::{Bound, RangeBounds},
+};
+
+/// 値を足す点があらかじめわかっているときに使う、座標圧縮された 2 次元 Fenwick 木です。
+///
+/// `x` 座標の Fenwick 木の各ノードが、そのノードに入る点の `y` 座標だけを持つ Fenwick 木を持ちます。
+/// メモリは点の個数を `n` として O(n log n) です。
+#[derive(Clone)]
+pub struct SparseFenwick2d<T = u32> {
+    xs: Vec<i64>,
+    // `ys[i - 1]` と `table[i - 1]` が `x` 座標の Fenwick 木のノード `i` です。
+    ys: Vec<Vec<i64>>,
+    table: Vec<Fenwick<T>>,
+}
+impl<T: Group> SparseFenwick2d<T> {
+    /// `add` をする可能性のある点をすべて与えて構築します。
+    pub fn new(points: &[(i64, i64)]) -> Self {
+        let mut xs = points.iter().map(|&(x, _)| x).collect::<Vec<_>>();
+        xs.sort_unstable();
+        xs.dedup();
+        let n = xs.len();
+        let mut ys = vec![Vec::new(); n];
+        for &(x, y) in points {
+            let i = xs.binary_search(&x).unwrap();
+            iter::successors(Some(i + 1), |&i| Some(i + lsb(i)))
+                .take_while(|&i| i <= n)
+                .for_each(|i| ys[i - 1].push(y));
+        }
+        for v in &mut ys {
+            v.sort_unstable();
+            v.dedup();
+        }
+        let table = ys
+            .iter()
+            .map(|v| Fenwick::from_slice(&vec![T::zero(); v.len()]))
+            .collect();
+        Self { xs, ys, table }
+    }
+    pub fn add(&mut self, x: i64, y: i64, w: T) {
+        let i = self
+            .xs
+            .binary_search(&x)
+            .expect("構築時に与えられていない点に足そうとしています。");
+        let n = self.xs.len();
+        for i in iter::successors(Some(i + 1), |&i| Some(i + lsb(i))).take_while(|&i| i <= n) {
+            let j = self.ys[i - 1]
+                .binary_search(&y)
+                .expect("構築時に与えられていない点に足そうとしています。");
+            self.table[i - 1].add(j, w);
+        }
+    }
+    /// `x` 座標が `xs` に、`y` 座標が `ys` に入る点の値の和を返します。
+    pub fn rect_sum(&self, xs: impl RangeBounds<i64>, ys: impl RangeBounds<i64>) -> T {
+        let x0 = start_index(&self.xs, xs.start_bound());
+        let x1 = end_index(&self.xs, xs.end_bound());
+        if x1 <= x0 {
+            return T::zero();
+        }
+        self.prefix_sum(x1, ys.start_bound(), ys.end_bound())
+            .sub(self.prefix_sum(x0, ys.start_bound(), ys.end_bound()))
+    }
+    // `x` 座標の添字が `..i` の点のうち、`y` 座標が範囲に入るものの和です。
+    fn prefix_sum(&self, i: usize, y_start: Bound<&i64>, y_end: Bound<&i64>) -> T {
+        iter::successors(Some(i), |&i| Some(i - lsb(i)))
+            .take_while(|&i| i != 0)
+            .map(|i| {
+                let ys = &self.ys[i - 1];
+                let j0 = start_index(ys, y_start);
+                let j1 = end_index(ys, y_end).max(j0);
+                let fenwick = &self.table[i - 1];
+                fenwick.prefix_sum(j1).sub(fenwick.prefix_sum(j0))
+            })
+            .fold(T::zero(), T::add)
+    }
+}
+impl<T: Group + fmt::Debug> fmt::Debug for SparseFenwick2d<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SparseFenwick2d")
+            .field("xs", &self.xs)
+            .field("ys", &self.ys)
+            .field("table", &self.table)
+            .finish()
+    }
+}
+
+fn start_index(sorted: &[i64], bound: Bound<&i64>) -> usize {
+    match bound {
+        Bound::Unbounded => 0,
+        Bound::Included(&x) => sorted.partition_point(|&y| y < x),
+        Bound::Excluded(&x) => sorted.partition_point(|&y| y <= x),
+    }
+}
+fn end_index(sorted: &[i64], bound: Bound<&i64>) -> usize {
+    match bound {
+        Bound::Unbounded => sorted.len(),
+        Bound::Included(&x) => sorted.partition_point(|&y| y <= x),
+        Bound::Excluded(&x) => sorted.partition_point(|&y| y < x),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    const TEST_COUNT: usize = 20;
+    const QUERY_COUNT: usize = 500;
+
+    fn gen_coordinate(rng: &mut StdRng, lim: i64) -> i64 {
+        rng.gen_range(-lim, lim)
+    }
+
+    #[test]
+    fn test_sparse() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for &lim in &[5, 1_000_000_000] {
+            for _ in 0..TEST_COUNT {
+                let n = rng.gen_range(1, 40);
+                let points = iter::repeat_with(|| {
+                    (gen_coordinate(&mut rng, lim), gen_coordinate(&mut rng, lim))
+                })
+                .take(n)
+                .collect::<Vec<_>>();
+                let mut fenwick = SparseFenwick2d::<i64>::new(&points);
+                let mut brute = vec![0; n];
+
+                for _ in 0..QUERY_COUNT {
+                    match rng.gen_range(0, 2) {
+                        0 => {
+                            let k = rng.gen_range(0, n);
+                            let w = rng.gen_range(-100, 100);
+                            let (x, y) = points[k];
+                            fenwick.add(x, y, w);
+                            brute[k] += w;
+                        }
+                        1 => {
+                            let mut x0 = gen_coordinate(&mut rng, lim + 1);
+                            let mut x1 = gen_coordinate(&mut rng, lim + 1);
+                            let y0 = gen_coordinate(&mut rng, lim + 1);
+                            let y1 = gen_coordinate(&mut rng, lim + 1);
+                            if x1 < x0 {
+                                std::mem::swap(&mut x0, &mut x1);
+                            }
+                            let expected = points
+                                .iter()
+                                .zip(&brute)
+                                .filter(|&(&(x, y), _)| x0 <= x && x < x1 && y0 <= y && y <= y1)
+                                .map(|(_, &w)| w)
+                                .sum::<i64>();
+                            assert_eq!(fenwick.rect_sum(x0..x1, y0..=y1), expected);
+                            let expected = points
+                                .iter()
+                                .zip(&brute)
+                                .filter(|&(&(x, _), _)| x0 < x)
+                                .map(|(_, &w)| w)
+                                .sum::<i64>();
+                            assert_eq!(
+                                fenwick.rect_sum((Bound::Excluded(x0), Bound::Unbounded), ..),
+                                expected
+                            );
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+                let cloned = fenwick.clone();
+                assert_eq!(cloned.rect_sum(.., ..), brute.iter().sum::<i64>());
+                assert_eq!(format!("{:?}", cloned), format!("{:?}", fenwick));
+            }
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use fenwick_u32::Group;
+use std::ops::Range;
+
+/// `D` 次元の Fenwick 木です。
+///
+/// 各軸に番兵の `0` 番目を足した `(dims[0] + 1) × ... × (dims[D - 1] + 1)` の表を、
+/// 最後の軸が連続するように一本の `Vec` に持ちます。`add`, `prefix_sum` は O(log^D n) です。
+#[derive(Debug, Clone, PartialEq)]
+pub struct FenwickNd<T, const D: usize> {
+    dims: [usize; D],
+    strides: [usize; D],
+    table: Vec<T>,
+}
+impl<T: Group, const D: usize> FenwickNd<T, D> {
+    /// 各軸の長さが `dims` の、すべて `T::zero()` の表を作ります。
+    pub fn new(dims: [usize; D]) -> Self {
+        let mut strides = [0; D];
+        let mut len = 1;
+        for k in (0..D).rev() {
+            strides[k] = len;
+            len *= dims[k] + 1;
+        }
+        Self {
+            dims,
+            strides,
+            table: vec![T::zero(); len],
+        }
+    }
+    /// 最後の軸が連続するように並べた値の列 `src` から、O(D n) で構築します。
+    pub fn from_flat(dims: [usize; D], src: &[T]) -> Self {
+        assert_eq!(
+            dims.iter().product::<usize>(),
+            src.len(),
+            "長さが合っていません。"
+        );
+        let mut res = Self::new(dims);
+        let mut index = [0; D];
+        for &x in src {
+            let offset = res.offset(&index);
+            res.table[offset] = x;
+            for k in (0..D).rev() {
+                index[k] += 1;
+                if index[k] < dims[k] {
+                    break;
+                }
+                index[k] = 0;
+            }
+        }
+        for (&n, &stride) in dims.iter().zip(&res.strides) {
+            for offset in 0..res.table.len() {
+                let i = offset / stride % (n + 1);
+                if i != 0 && i + lsb(i) <= n {
+                    let x = res.table[offset];
+                    let next = offset + lsb(i) * stride;
+                    res.table[next] = res.table[next].add(x);
+                }
+            }
+        }
+        res
+    }
+    /// 各軸の長さを返します。
+    pub fn dims(&self) -> [usize; D] {
+        self.dims
+    }
+    pub fn add(&mut self, index: [usize; D], x: T) {
+        assert!(
+            index.iter().zip(&self.dims).all(|(&i, &n)| i < n),
+            "範囲外です。"
+        );
+        self.add_rec(0, 0, &index, x);
+    }
+    pub fn sub(&mut self, index: [usize; D], x: T) {
+        self.add(index, T::zero().sub(x));
+    }
+    /// 各軸 `k` について添字が `..index[k]` である値の和を返します。
+    pub fn prefix_sum(&self, index: [usize; D]) -> T {
+        assert!(
+            index.iter().zip(&self.dims).all(|(&i, &n)| i <= n),
+            "範囲外です。"
+        );
+        self.prefix_sum_rec(0, 0, &index)
+    }
+    /// 各軸 `k` について添字が `ranges[k]` に入る値の和を、2^D 個の角の包除で計算します。
+    pub fn box_sum(&self, ranges: [Range<usize>; D]) -> T {
+        assert!(ranges.iter().all(|range| range.start <= range.end));
+        let mut res = T::zero();
+        for mask in 0..1usize << D {
+            let mut corner = [0; D];
+            for (k, range) in ranges.iter().enumerate() {
+                corner[k] = if mask >> k & 1 == 0 {
+                    range.end
+                } else {
+                    range.start
+                };
+            }
+            let x = self.prefix_sum(corner);
+            res = if mask.count_ones() % 2 == 0 {
+                res.add(x)
+            } else {
+                res.sub(x)
+            };
+        }
+        res
+    }
+    fn offset(&self, index: &[usize; D]) -> usize {
+        index
+            .iter()
+            .zip(&self.strides)
+            .map(|(&i, &stride)| (i + 1) * stride)
+            .sum()
+    }
+    fn add_rec(&mut self, k: usize, offset: usize, index: &[usize; D], x: T) {
+        if k == D {
+            self.table[offset] = self.table[offset].add(x);
+            return;
+        }
+        let mut i = index[k] + 1;
+        while i <= self.dims[k] {
+            self.add_rec(k + 1, offset + i * self.strides[k], index, x);
+            i += lsb(i);
+        }
+    }
+    fn prefix_sum_rec(&self, k: usize, offset: usize, index: &[usize; D]) -> T {
+        if k == D {
+            return self.table[offset];
+        }
+        let mut res = T::zero();
+        let mut i = index[k];
+        while i != 0 {
+            res = res.add(self.prefix_sum_rec(k + 1, offset + i * self.strides[k], index));
+            i -= lsb(i);
+        }
+        res
+    }
+}
+#[inline]
+fn lsb(i: usize) -> usize {
+    i & i.wrapping_neg()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fenwick_2d_u32::Fenwick2d;
+    use fenwick_u32::Fenwick;
+    use rand::prelude::*;
+    use std::iter;
+
+    const TEST_COUNT: usize = 20;
+    const QUERY_COUNT: usize = 500;
+    const VALUE_LIMIT: u32 = 100;
+
+    fn gen_range(rng: &mut StdRng, n: usize) -> Range<usize> {
+        let mut l = rng.gen_range(0, n + 1);
+        let mut r = rng.gen_range(0, n + 1);
+        if r < l {
+            std::mem::swap(&mut l, &mut r);
+        }
+        l..r
+    }
+
+    #[test]
+    fn test_agree_with_1d() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let n = rng.gen_range(0, 30);
+            let a = iter::repeat_with(|| rng.gen_range(0, VALUE_LIMIT))
+                .take(n)
+                .collect::<Vec<_>>();
+            let mut fenwick = Fenwick::from_slice(&a);
+            let mut nd = FenwickNd::<u32, 1>::from_flat([n], &a);
+
+            for _ in 0..QUERY_COUNT {
+                match rng.gen_range(0, 2) {
+                    0 if n != 0 => {
+                        let i = rng.gen_range(0, n);
+                        let x = rng.gen_range(0, VALUE_LIMIT);
+                        fenwick.add(i, x);
+                        nd.add([i], x);
+                    }
+                    _ => {
+                        let i = rng.gen_range(0, n + 1);
+                        assert_eq!(nd.prefix_sum([i]), fenwick.prefix_sum(i));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_agree_with_2d() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let h = rng.gen_range(1, 15);
+            let w = rng.gen_range(1, 15);
+            let a = iter::repeat_with(|| {
+                iter::repeat_with(|| rng.gen_range(0, VALUE_LIMIT))
+                    .take(w)
+                    .collect::<Vec<_>>()
+            })
+            .take(h)
+            .collect::<Vec<_>>();
+            let mut fenwick = Fenwick2d::from_slice_vec(&a);
+            let mut nd = FenwickNd::<u32, 2>::from_flat([h, w], &a.concat());
+
+            for _ in 0..QUERY_COUNT {
+                match rng.gen_range(0, 3) {
+                    0 => {
+                        let i = rng.gen_range(0, h);
+                        let j = rng.gen_range(0, w);
+                        let x = rng.gen_range(0, VALUE_LIMIT);
+                        fenwick.add(i, j, x);
+                        nd.add([i, j], x);
+                    }
+                    1 => {
+                        let i = rng.gen_range(0, h + 1);
+                        let j = rng.gen_range(0, w + 1);
+                        assert_eq!(nd.prefix_sum([i, j]), fenwick.double_prefix_sum(i, j));
+                    }
+                    2 => {
+                        let rows = gen_range(&mut rng, h);
+                        let cols = gen_range(&mut rng, w);
+                        assert_eq!(
+                            nd.box_sum([rows.clone(), cols.clone()]),
+                            fenwick.rect_sum(rows, cols)
+                        );
+                    }
+                    _ => unreachable!(),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_3d() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let dims = [
+                rng.gen_range(1, 6),
+                rng.gen_range(1, 6),
+                rng.gen_range(1, 6),
+            ];
+            let mut brute = vec![vec![vec![0i64; dims[2]]; dims[1]]; dims[0]];
+            let mut nd = FenwickNd::<i64, 3>::new(dims);
+            assert_eq!(nd.dims(), dims);
+
+            for _ in 0..QUERY_COUNT {
+                match rng.gen_range(0, 2) {
+                    0 => {
+                        let index = [
+                            rng.gen_range(0, dims[0]),
+                            rng.gen_range(0, dims[1]),
+                            rng.gen_range(0, dims[2]),
+                        ];
+                        let x = rng.gen_range(-100, 100);
+                        brute[index[0]][index[1]][index[2]] += x;
+                        nd.add(index, x);
+                    }
+                    1 => {
+                        let ranges = [
+                            gen_range(&mut rng, dims[0]),
+                            gen_range(&mut rng, dims[1]),
+                            gen_range(&mut rng, dims[2]),
+                        ];
+                        let expected = brute[ranges[0].clone()]
+                            .iter()
+                            .flat_map(|v| v[ranges[1].clone()].iter())
+                            .flat_map(|v| v[ranges[2].clone()].iter())
+                            .sum::<i64>();
+                        assert_eq!(nd.box_sum(ranges), expected);
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            let src = brute.concat().concat();
+            let built = FenwickNd::<i64, 3>::from_flat(dims, &src);
+            assert_eq!(built, nd);
+        }
+    }
+}
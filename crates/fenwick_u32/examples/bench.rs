@@ -0,0 +1,110 @@
+//! `Fenwick` と `BlockedFenwick` の速さを比べます。
+//!
+//! ```text
+//! cargo run --release --example bench
+//! ```
+use fenwick_u32::{BlockedFenwick, Fenwick};
+use rand::prelude::*;
+use std::{hint::black_box, iter, time::Instant};
+
+const QUERY_COUNT: usize = 1_000_000;
+
+trait Layout {
+    const NAME: &'static str;
+    fn from_slice(src: &[u32]) -> Self;
+    fn prefix_sum(&self, i: usize) -> u32;
+    fn add(&mut self, i: usize, x: u32);
+    fn upper_bound(&self, x: &u32) -> usize;
+}
+
+impl Layout for Fenwick {
+    const NAME: &'static str = "Fenwick";
+    fn from_slice(src: &[u32]) -> Self {
+        Fenwick::from_slice(src)
+    }
+    fn prefix_sum(&self, i: usize) -> u32 {
+        Fenwick::prefix_sum(self, i)
+    }
+    fn add(&mut self, i: usize, x: u32) {
+        Fenwick::add(self, i, x)
+    }
+    fn upper_bound(&self, x: &u32) -> usize {
+        Fenwick::upper_bound(self, x)
+    }
+}
+
+macro_rules! impl_layout_blocked {
+    ($($b:expr),*) => {$(
+        impl Layout for BlockedFenwick<u32, $b> {
+            const NAME: &'static str = concat!("BlockedFenwick<", stringify!($b), ">");
+            fn from_slice(src: &[u32]) -> Self {
+                BlockedFenwick::from_slice(src)
+            }
+            fn prefix_sum(&self, i: usize) -> u32 {
+                BlockedFenwick::prefix_sum(self, i)
+            }
+            fn add(&mut self, i: usize, x: u32) {
+                BlockedFenwick::add(self, i, x)
+            }
+            fn upper_bound(&self, x: &u32) -> usize {
+                BlockedFenwick::upper_bound(self, x)
+            }
+        }
+    )*};
+}
+impl_layout_blocked!(16, 64, 256);
+
+fn bench<T: Layout>(a: &[u32], queries: &[(usize, u32)]) {
+    let start = Instant::now();
+    let mut fenwick = T::from_slice(a);
+    let build = start.elapsed();
+
+    let start = Instant::now();
+    for &(i, x) in queries {
+        fenwick.add(i, x % 4);
+    }
+    let add = start.elapsed();
+
+    let start = Instant::now();
+    for &(i, _) in queries {
+        black_box(fenwick.prefix_sum(i));
+    }
+    let prefix_sum = start.elapsed();
+
+    let total = fenwick.prefix_sum(a.len());
+    let start = Instant::now();
+    for &(_, x) in queries {
+        black_box(fenwick.upper_bound(&(x % (total + 1))));
+    }
+    let upper_bound = start.elapsed();
+
+    println!(
+        "{:>10} {:<20} {:>10.2?} {:>10.2?} {:>10.2?} {:>10.2?}",
+        a.len(),
+        T::NAME,
+        build,
+        add,
+        prefix_sum,
+        upper_bound
+    );
+}
+
+fn main() {
+    let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+    println!(
+        "{:>10} {:<20} {:>10} {:>10} {:>10} {:>10}",
+        "len", "layout", "build", "add", "prefix_sum", "upper_bound"
+    );
+    for &n in &[1_000, 100_000, 10_000_000, 100_000_000] {
+        let a = iter::repeat_with(|| rng.gen_range(0, 4))
+            .take(n)
+            .collect::<Vec<u32>>();
+        let queries = iter::repeat_with(|| (rng.gen_range(0, n), rng.gen_range(0, 1 << 30)))
+            .take(QUERY_COUNT)
+            .collect::<Vec<_>>();
+        bench::<Fenwick>(&a, &queries);
+        bench::<BlockedFenwick<u32, 16>>(&a, &queries);
+        bench::<BlockedFenwick<u32, 64>>(&a, &queries);
+        bench::<BlockedFenwick<u32, 256>>(&a, &queries);
+    }
+}
@@ -0,0 +1,155 @@
+//! 3 次元の半順序についての数え上げを、CDQ 分割統治と `Fenwick` で行います。
+use crate::{Fenwick, Group};
+
+/// 各点 `i` に対して、`j != i` かつ `a[j] <= a[i]`, `b[j] <= b[i]`, `c[j] <= c[i]` なる点 `j` の
+/// 重み `w[j]` の和を返します。点は `(a, b, c, w)` で与えます。
+///
+/// 点の個数を `n` として O(n log^2 n) です。座標がすべて等しい点は互いに支配しあうものとして数えます。
+pub fn dominated_sums<A, B, C, T>(points: &[(A, B, C, T)]) -> Vec<T>
+where
+    A: Ord + Copy,
+    B: Ord + Copy,
+    C: Ord + Copy,
+    T: Group,
+{
+    let mut cs = points.iter().map(|&(_, _, c, _)| c).collect::<Vec<_>>();
+    cs.sort();
+    cs.dedup();
+    let mut order = (0..points.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| {
+        let (a, b, c, _) = points[i];
+        (a, b, c)
+    });
+
+    // 座標の等しい点をまとめます。`group[i]` は点 `i` の入るまとまりの番号です。
+    let mut unique = Vec::<Point<B, T>>::new();
+    let mut group = vec![0; points.len()];
+    for (k, &i) in order.iter().enumerate() {
+        let (a, b, c, w) = points[i];
+        let same = k != 0 && {
+            let (a1, b1, c1, _) = points[order[k - 1]];
+            (a1, b1, c1) == (a, b, c)
+        };
+        if same {
+            let last = unique.last_mut().unwrap();
+            last.w = last.w.add(w);
+        } else {
+            unique.push(Point {
+                b,
+                c: cs.binary_search(&c).unwrap(),
+                w,
+                ans: T::zero(),
+            });
+        }
+        group[i] = unique.len() - 1;
+    }
+
+    let mut fenwick = Fenwick::from_slice(&vec![T::zero(); cs.len()]);
+    let mut index = (0..unique.len()).collect::<Vec<_>>();
+    solve(&mut unique, &mut index, &mut fenwick);
+
+    points
+        .iter()
+        .zip(&group)
+        .map(|(&(_, _, _, w), &g)| {
+            let p = &unique[g];
+            p.ans.add(p.w).sub(w)
+        })
+        .collect()
+}
+
+struct Point<B, T> {
+    b: B,
+    c: usize,
+    w: T,
+    ans: T,
+}
+
+// `index` は `(a, b, c)` の辞書順に並んだ相異なる点の番号で、これを `b` の昇順に並べ替えながら、
+// 右半分の各点に左半分の支配される点の重みを足します。
+fn solve<B: Ord + Copy, T: Group>(
+    points: &mut [Point<B, T>],
+    index: &mut [usize],
+    fenwick: &mut Fenwick<T>,
+) {
+    if index.len() <= 1 {
+        return;
+    }
+    let mid = index.len() / 2;
+    {
+        let (left, right) = index.split_at_mut(mid);
+        solve(points, left, fenwick);
+        solve(points, right, fenwick);
+    }
+    let (left, right) = index.split_at(mid);
+    let mut merged = Vec::with_capacity(index.len());
+    let mut k = 0;
+    for &i in right {
+        while k < left.len() && points[left[k]].b <= points[i].b {
+            let p = &points[left[k]];
+            fenwick.add(p.c, p.w);
+            merged.push(left[k]);
+            k += 1;
+        }
+        let sum = fenwick.prefix_sum(points[i].c + 1);
+        points[i].ans = points[i].ans.add(sum);
+        merged.push(i);
+    }
+    for &j in &left[..k] {
+        let p = &points[j];
+        fenwick.sub(p.c, p.w);
+    }
+    merged.extend_from_slice(&left[k..]);
+    index.copy_from_slice(&merged);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+    use std::iter;
+
+    const TEST_COUNT: usize = 200;
+
+    fn brute(points: &[(i32, i32, i32, i64)]) -> Vec<i64> {
+        points
+            .iter()
+            .enumerate()
+            .map(|(i, &(a, b, c, _))| {
+                points
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, &(a1, b1, c1, _))| j != i && a1 <= a && b1 <= b && c1 <= c)
+                    .map(|(_, &(_, _, _, w))| w)
+                    .sum()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_dominated_sums() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let n = rng.gen_range(0, 40);
+            let lim = rng.gen_range(1, 10);
+            let points = iter::repeat_with(|| {
+                (
+                    rng.gen_range(0, lim),
+                    rng.gen_range(0, lim),
+                    rng.gen_range(-lim, lim),
+                    rng.gen_range(-100, 100),
+                )
+            })
+            .take(n)
+            .collect::<Vec<_>>();
+            assert_eq!(dominated_sums(&points), brute(&points), "{:?}", &points);
+        }
+    }
+
+    #[test]
+    fn test_count() {
+        let points = [(1, 1, 1, 1), (2, 2, 2, 1), (1, 1, 1, 1), (3, 0, 3, 1)];
+        assert_eq!(dominated_sums::<_, _, _, u32>(&points), vec![1, 2, 1, 0]);
+    }
+}
@@ -0,0 +1,22 @@
+/// 座標圧縮をします。各要素を、相異なる値の中での順位に置き換えた列と、相異なる値の個数を返します。
+pub fn compress<T: Ord>(a: &[T]) -> (Vec<usize>, usize) {
+    let mut values = a.iter().collect::<Vec<_>>();
+    values.sort();
+    values.dedup();
+    let res = a
+        .iter()
+        .map(|x| values.binary_search(&x).unwrap())
+        .collect::<Vec<_>>();
+    (res, values.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compress() {
+        assert_eq!(compress(&[30, 10, 20, 10]), (vec![2, 0, 1, 0], 3));
+        assert_eq!(compress::<i32>(&[]), (vec![], 0));
+    }
+}
@@ -0,0 +1,160 @@
+//! 重み付きの点の集合に対する、長方形領域の和のクエリをオフラインで処理します。
+//!
+//! どちらの関数も座標圧縮をして `x` 座標について掃き、`y` 座標の `Fenwick` で答えます。
+//! 点の個数を `n`、クエリの個数を `q` として O((n + q) log n) です。
+use crate::{Fenwick, Group};
+use std::ops::Range;
+
+/// 各クエリ `(x, y)` に対して、`x' <= x` かつ `y' <= y` なる点 `(x', y', w)` の `w` の和を、
+/// クエリの順に返します。
+pub fn dominance_sums<C: Ord + Copy, T: Group>(points: &[(C, C, T)], queries: &[(C, C)]) -> Vec<T> {
+    corner_sums(points, queries, true)
+}
+
+/// 各クエリ `(xs, ys)` に対して、`x' ∈ xs` かつ `y' ∈ ys` なる点 `(x', y', w)` の `w` の和を、
+/// クエリの順に返します。
+pub fn rect_sums<C: Ord + Copy, T: Group>(
+    points: &[(C, C, T)],
+    queries: &[(Range<C>, Range<C>)],
+) -> Vec<T> {
+    assert!(
+        queries
+            .iter()
+            .all(|(xs, ys)| xs.start <= xs.end && ys.start <= ys.end),
+        "範囲の始点が終点より大きいです。"
+    );
+    let corners = queries
+        .iter()
+        .flat_map(|(xs, ys)| {
+            vec![
+                (xs.end, ys.end),
+                (xs.start, ys.end),
+                (xs.end, ys.start),
+                (xs.start, ys.start),
+            ]
+        })
+        .collect::<Vec<_>>();
+    corner_sums(points, &corners, false)
+        .chunks(4)
+        .map(|v| v[0].sub(v[1]).sub(v[2]).add(v[3]))
+        .collect()
+}
+
+// `inclusive` ならば `x' <= x` かつ `y' <= y`、そうでなければ `x' < x` かつ `y' < y` なる点の和です。
+fn corner_sums<C: Ord + Copy, T: Group>(
+    points: &[(C, C, T)],
+    corners: &[(C, C)],
+    inclusive: bool,
+) -> Vec<T> {
+    let before = |a: &C, b: &C| if inclusive { a <= b } else { a < b };
+    let mut ys = points.iter().map(|&(_, y, _)| y).collect::<Vec<_>>();
+    ys.sort();
+    ys.dedup();
+    let mut points = points.to_vec();
+    points.sort_by_key(|&(x, _, _)| x);
+    let mut order = (0..corners.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| corners[i].0);
+
+    let mut fenwick = Fenwick::from_slice(&vec![T::zero(); ys.len()]);
+    let mut res = vec![T::zero(); corners.len()];
+    let mut points = points.into_iter().peekable();
+    for i in order {
+        let (x, y) = corners[i];
+        while let Some((_, y, w)) = points.next_if(|(x1, _, _)| before(x1, &x)) {
+            fenwick.add(ys.binary_search(&y).unwrap(), w);
+        }
+        res[i] = fenwick.prefix_sum(ys.partition_point(|y1| before(y1, &y)));
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+    use std::iter;
+
+    const TEST_COUNT: usize = 100;
+
+    fn gen_range(rng: &mut StdRng, lim: i32) -> Range<i32> {
+        let mut l = rng.gen_range(-lim - 1, lim + 2);
+        let mut r = rng.gen_range(-lim - 1, lim + 2);
+        if r < l {
+            std::mem::swap(&mut l, &mut r);
+        }
+        l..r
+    }
+
+    #[test]
+    fn test_dominance_sums() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let lim = rng.gen_range(1, 20);
+            let n = rng.gen_range(0, 30);
+            let points = iter::repeat_with(|| {
+                (
+                    rng.gen_range(-lim, lim + 1),
+                    rng.gen_range(-lim, lim + 1),
+                    rng.gen_range(-100, 100),
+                )
+            })
+            .take(n)
+            .collect::<Vec<(i32, i32, i64)>>();
+            let q = rng.gen_range(0, 30);
+            let queries = iter::repeat_with(|| {
+                (
+                    rng.gen_range(-lim - 1, lim + 2),
+                    rng.gen_range(-lim - 1, lim + 2),
+                )
+            })
+            .take(q)
+            .collect::<Vec<_>>();
+            let expected = queries
+                .iter()
+                .map(|&(x, y)| {
+                    points
+                        .iter()
+                        .filter(|&&(x1, y1, _)| x1 <= x && y1 <= y)
+                        .map(|&(_, _, w)| w)
+                        .sum::<i64>()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(dominance_sums(&points, &queries), expected);
+        }
+    }
+
+    #[test]
+    fn test_rect_sums() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let lim = rng.gen_range(1, 20);
+            let n = rng.gen_range(0, 30);
+            let points = iter::repeat_with(|| {
+                (
+                    rng.gen_range(-lim, lim + 1),
+                    rng.gen_range(-lim, lim + 1),
+                    1,
+                )
+            })
+            .take(n)
+            .collect::<Vec<(i32, i32, u32)>>();
+            let q = rng.gen_range(0, 30);
+            let queries =
+                iter::repeat_with(|| (gen_range(&mut rng, lim), gen_range(&mut rng, lim)))
+                    .take(q)
+                    .collect::<Vec<_>>();
+            let expected = queries
+                .iter()
+                .map(|(xs, ys)| {
+                    points
+                        .iter()
+                        .filter(|&(x, y, _)| xs.contains(x) && ys.contains(y))
+                        .count() as u32
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(rect_sums(&points, &queries), expected);
+        }
+    }
+}
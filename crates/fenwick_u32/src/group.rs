@@ -0,0 +1,39 @@
+/// 可換群です。Fenwick 木の値の型に使います。
+///
+/// 整数型には wrapping な演算で実装しているので、途中の値があふれても、
+/// 最終的な答えが型に収まっていれば正しく計算できます。
+/// 数え上げ用に `fp::F998244353` にも実装しています。
+pub trait Group: Copy {
+    fn zero() -> Self;
+    fn add(self, rhs: Self) -> Self;
+    fn sub(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_group_int {
+    ($($t:ty),*) => {$(
+        impl Group for $t {
+            fn zero() -> Self {
+                0
+            }
+            fn add(self, rhs: Self) -> Self {
+                self.wrapping_add(rhs)
+            }
+            fn sub(self, rhs: Self) -> Self {
+                self.wrapping_sub(rhs)
+            }
+        }
+    )*};
+}
+impl_group_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl Group for fp::F998244353 {
+    fn zero() -> Self {
+        Self::new(0)
+    }
+    fn add(self, rhs: Self) -> Self {
+        self + rhs
+    }
+    fn sub(self, rhs: Self) -> Self {
+        self - rhs
+    }
+}
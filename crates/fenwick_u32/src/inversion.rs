@@ -0,0 +1,158 @@
+use crate::{compress, Fenwick};
+
+/// `i < j` かつ `a[i] > a[j]` なる組 `(i, j)` の個数を返します。
+pub fn inversion_number<T: Ord>(a: &[T]) -> u64 {
+    let (a, n) = compress(a);
+    let mut fenwick = Fenwick::from_slice(&vec![0; n]);
+    let mut res = 0;
+    for (i, &x) in a.iter().enumerate() {
+        res += (i as u32 - fenwick.prefix_sum(x + 1)) as u64;
+        fenwick.add(x, 1);
+    }
+    res
+}
+
+/// 各 `i` に対して、`j < i` かつ `a[j] < a[i]` なる `j` の個数を返します。
+pub fn smaller_to_left<T: Ord>(a: &[T]) -> Vec<usize> {
+    let (a, n) = compress(a);
+    let mut fenwick = Fenwick::from_slice(&vec![0; n]);
+    a.iter()
+        .map(|&x| {
+            let res = fenwick.prefix_sum(x) as usize;
+            fenwick.add(x, 1);
+            res
+        })
+        .collect()
+}
+
+/// 各 `i` に対して、`i < j` かつ `a[i] < a[j]` なる `j` の個数を返します。
+pub fn greater_to_right<T: Ord>(a: &[T]) -> Vec<usize> {
+    let (a, n) = compress(a);
+    let mut fenwick = Fenwick::from_slice(&vec![0; n]);
+    let mut res = vec![0; a.len()];
+    for (i, &x) in a.iter().enumerate().rev() {
+        res[i] = (fenwick.prefix_sum(n) - fenwick.prefix_sum(x + 1)) as usize;
+        fenwick.add(x, 1);
+    }
+    res
+}
+
+/// 相異なる要素からなる同じ集合の 2 つの並べ方 `a`, `b` について、
+/// 順序が食い違う要素の組の個数を返します。
+pub fn kendall_tau_distance<T: Ord>(a: &[T], b: &[T]) -> u64 {
+    assert_eq!(a.len(), b.len(), "長さが違います。");
+    let mut position = a
+        .iter()
+        .enumerate()
+        .map(|(i, x)| (x, i))
+        .collect::<Vec<_>>();
+    position.sort();
+    assert!(
+        position.windows(2).all(|w| w[0].0 != w[1].0),
+        "要素が重複しています。"
+    );
+    let b = b
+        .iter()
+        .map(|x| {
+            let i = position
+                .binary_search_by(|&(y, _)| y.cmp(x))
+                .expect("a と b の要素の集合が違います。");
+            position[i].1
+        })
+        .collect::<Vec<_>>();
+    let mut seen = vec![false; b.len()];
+    assert!(
+        b.iter().all(|&i| !std::mem::replace(&mut seen[i], true)),
+        "b の要素が重複しています。"
+    );
+    inversion_number(&b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+    use std::iter;
+
+    const TEST_COUNT: usize = 200;
+
+    fn gen_vec(rng: &mut StdRng) -> Vec<i64> {
+        let n = rng.gen_range(0, 30);
+        let lim = rng.gen_range(1, 40);
+        iter::repeat_with(|| rng.gen_range(-lim, lim))
+            .take(n)
+            .collect()
+    }
+
+    #[test]
+    fn test_inversion_number() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+        for _ in 0..TEST_COUNT {
+            let a = gen_vec(&mut rng);
+            let n = a.len();
+            let expected = (0..n)
+                .flat_map(|j| (0..j).map(move |i| (i, j)))
+                .filter(|&(i, j)| a[i] > a[j])
+                .count() as u64;
+            assert_eq!(inversion_number(&a), expected, "a = {:?}", &a);
+        }
+    }
+
+    #[test]
+    fn test_smaller_to_left() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+        for _ in 0..TEST_COUNT {
+            let a = gen_vec(&mut rng);
+            let expected = (0..a.len())
+                .map(|i| a[..i].iter().filter(|&&x| x < a[i]).count())
+                .collect::<Vec<_>>();
+            assert_eq!(smaller_to_left(&a), expected, "a = {:?}", &a);
+        }
+    }
+
+    #[test]
+    fn test_greater_to_right() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+        for _ in 0..TEST_COUNT {
+            let a = gen_vec(&mut rng);
+            let expected = (0..a.len())
+                .map(|i| a[i + 1..].iter().filter(|&&x| a[i] < x).count())
+                .collect::<Vec<_>>();
+            assert_eq!(greater_to_right(&a), expected, "a = {:?}", &a);
+        }
+    }
+
+    #[test]
+    fn test_kendall_tau_distance() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+        for _ in 0..TEST_COUNT {
+            let n = rng.gen_range(0, 30);
+            let mut a = (0..n as i64).map(|x| x * 3 - 10).collect::<Vec<_>>();
+            a.shuffle(&mut rng);
+            let mut b = a.clone();
+            b.shuffle(&mut rng);
+            let expected = (0..n)
+                .flat_map(|j| (0..j).map(move |i| (i, j)))
+                .filter(|&(i, j)| {
+                    let bi = b.iter().position(|&x| x == a[i]).unwrap();
+                    let bj = b.iter().position(|&x| x == a[j]).unwrap();
+                    bi > bj
+                })
+                .count() as u64;
+            assert_eq!(kendall_tau_distance(&a, &b), expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "b の要素が重複しています。")]
+    fn test_kendall_tau_distance_duplicate_in_b() {
+        kendall_tau_distance(&[1, 2, 3], &[1, 1, 2]);
+    }
+
+    #[test]
+    fn test_large_answer() {
+        let n = 100_000u64;
+        let a = (0..n).rev().collect::<Vec<_>>();
+        assert_eq!(inversion_number(&a), n * (n - 1) / 2);
+    }
+}
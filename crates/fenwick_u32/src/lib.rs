@@ -1,50 +1,139 @@
-use std::iter;
+mod blocked;
+pub mod cdq;
+mod compress;
+pub mod dominance;
+mod group;
+pub mod inversion;
+mod max;
+mod monoid;
+mod multiset;
+#[cfg(feature = "rayon")]
+mod par;
+mod persistent;
+mod sparse;
 
-pub struct Fenwick {
-    pub table: Vec<u32>,
+pub use blocked::BlockedFenwick;
+pub use compress::compress;
+pub use group::Group;
+pub use max::{FenwickMax, FenwickMin};
+pub use monoid::FenwickMonoid;
+pub use multiset::{CompressedFenwickMultiset, FenwickMultiset};
+pub use persistent::PersistentFenwick;
+pub use sparse::SparseFenwick;
+
+use std::{fmt, iter};
+
+#[derive(Clone)]
+pub struct Fenwick<T = u32> {
+    table: Vec<T>,
 }
-impl Fenwick {
-    pub fn new(zero: u32) -> Self {
+impl<T: Group> Fenwick<T> {
+    pub fn new(zero: T) -> Self {
         Self { table: vec![zero] }
     }
-    pub fn push(&mut self, x: u32) {
+    pub fn with_capacity(zero: T, capacity: usize) -> Self {
+        let mut table = Vec::with_capacity(capacity + 1);
+        table.push(zero);
+        Self { table }
+    }
+    pub fn len(&self) -> usize {
+        self.table.len() - 1
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn reserve(&mut self, additional: usize) {
+        self.table.reserve(additional);
+    }
+    pub fn push(&mut self, x: T) {
         let n = self.table.len();
         let lsb_n = lsb(n);
-        let x = x + iter::successors(Some(1), |&d| Some(2 * d))
+        let x = iter::successors(Some(1), |&d| Some(2 * d))
             .take_while(|&d| d != lsb_n)
             .map(|i| self.table[n - i])
-            .sum::<u32>();
+            .fold(x, T::add);
         self.table.push(x);
     }
-    pub fn from_slice(src: &[u32]) -> Self {
-        let mut table = vec![0; src.len() + 1];
+    /// 最後の要素を削除して、その値を返します。
+    pub fn pop(&mut self) -> Option<T> {
+        let n = self.len();
+        if n == 0 {
+            None
+        } else {
+            let x = self.prefix_sum(n).sub(self.prefix_sum(n - 1));
+            self.table.pop();
+            Some(x)
+        }
+    }
+    pub fn truncate(&mut self, len: usize) {
+        self.table.truncate(len + 1);
+    }
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+    pub fn from_slice(src: &[T]) -> Self {
+        let mut table = vec![T::zero(); src.len() + 1];
         table[1..].copy_from_slice(src);
         let n = table.len();
         (1..n)
             .map(|i| (i, i + lsb(i)))
             .filter(|&(_, j)| j < n)
-            .for_each(|(i, j)| table[j] += table[i]);
+            .for_each(|(i, j)| table[j] = table[j].add(table[i]));
         Self { table }
     }
-    pub fn prefix_sum(&self, i: usize) -> u32 {
+    /// 元の値の列を O(n) で復元します。
+    pub fn to_vec(&self) -> Vec<T> {
+        let mut table = self.table.clone();
+        let n = table.len();
+        (1..n)
+            .rev()
+            .map(|i| (i, i + lsb(i)))
+            .filter(|&(_, j)| j < n)
+            .for_each(|(i, j)| table[j] = table[j].sub(table[i]));
+        table.remove(0);
+        table
+    }
+    pub fn prefix_sum(&self, i: usize) -> T {
         iter::successors(Some(i), |&i| Some(i - lsb(i)))
             .take_while(|&i| i != 0)
             .map(|i| self.table[i])
-            .sum()
+            .fold(T::zero(), T::add)
     }
-    pub fn add(&mut self, i: usize, x: u32) {
+    pub fn add(&mut self, i: usize, x: T) {
         let n = self.table.len();
         iter::successors(Some(i + 1), |&i| Some(i + lsb(i)))
             .take_while(|&i| i < n)
-            .for_each(|i| self.table[i] += x)
+            .for_each(|i| self.table[i] = self.table[i].add(x))
+    }
+    pub fn sub(&mut self, i: usize, x: T) {
+        let n = self.table.len();
+        iter::successors(Some(i + 1), |&i| Some(i + lsb(i)))
+            .take_while(|&i| i < n)
+            .for_each(|i| self.table[i] = self.table[i].sub(x))
+    }
+}
+impl<T: Group + Send + Sync> Fenwick<T> {
+    /// `prefix_sum` をまとめて計算します。`rayon` feature が有効ならば並列に計算します。
+    pub fn prefix_sums(&self, indices: &[usize]) -> Vec<T> {
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            indices.par_iter().map(|&i| self.prefix_sum(i)).collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            indices.iter().map(|&i| self.prefix_sum(i)).collect()
+        }
     }
-    pub fn upper_bound(&self, x: &u32) -> usize {
+}
+impl<T: Group + Ord> Fenwick<T> {
+    pub fn upper_bound(&self, x: &T) -> usize {
         let mut d = self.table.len().next_power_of_two() / 2;
         let mut j = 0;
-        let mut now = 0;
+        let mut now = T::zero();
         while d != 0 {
             if d + j < self.table.len() {
-                let next = now + self.table[d + j];
+                let next = now.add(self.table[d + j]);
                 if &next <= x {
                     now = next;
                     j += d;
@@ -55,6 +144,22 @@ impl Fenwick {
         j
     }
 }
+impl<T: Group> Default for Fenwick<T> {
+    fn default() -> Self {
+        Self::new(T::zero())
+    }
+}
+// `table[0]` は `new` などに与えた値のままで使われないので、比べません。
+impl<T: PartialEq> PartialEq for Fenwick<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.table[1..] == other.table[1..]
+    }
+}
+impl<T: Group + fmt::Debug> fmt::Debug for Fenwick<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.to_vec()).finish()
+    }
+}
 #[inline]
 fn lsb(i: usize) -> usize {
     i & !(i.saturating_sub(1))
@@ -148,4 +253,123 @@ mod tests {
             println!();
         }
     }
+
+    #[test]
+    fn test_shrink() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let mut a = Vec::new();
+            let mut fenwick = Fenwick::with_capacity(0, 20);
+
+            for _ in 0..QUERY_COUNT {
+                match rng.gen_range(0, 10) {
+                    0..=5 => {
+                        let x = gen_value(&mut rng);
+                        a.push(x);
+                        fenwick.push(x);
+                    }
+                    6..=8 => assert_eq!(fenwick.pop(), a.pop()),
+                    9 => {
+                        let len = rng.gen_range(0, a.len() + 2);
+                        a.truncate(len);
+                        fenwick.truncate(len);
+                    }
+                    _ => unreachable!(),
+                }
+                assert_eq!(fenwick.len(), a.len());
+                assert_eq!(fenwick, Fenwick::from_slice(&a));
+                assert_eq!(fenwick.to_vec(), a);
+            }
+            fenwick.clear();
+            assert!(fenwick.is_empty());
+            assert_eq!(fenwick, Fenwick::default());
+        }
+    }
+
+    #[test]
+    fn test_eq_and_debug() {
+        assert_eq!(Fenwick::<u32>::new(5), Fenwick::default());
+        assert_eq!(Fenwick::<u32>::with_capacity(5, 10), Fenwick::default());
+        let mut fenwick = Fenwick::new(5);
+        fenwick.push(1);
+        assert_eq!(fenwick, Fenwick::from_slice(&[1]));
+        assert_ne!(fenwick, Fenwick::from_slice(&[2]));
+        assert_ne!(fenwick, Fenwick::from_slice(&[1, 0]));
+        assert_eq!(format!("{:?}", fenwick), "[1]");
+        assert_eq!(
+            format!("{:?}", Fenwick::from_slice(&[3, 1, 4])),
+            "[3, 1, 4]"
+        );
+    }
+
+    #[test]
+    fn test_signed() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let n = rng.gen_range(1, 20);
+            let mut a = iter::repeat_with(|| rng.gen_range(-100, 100))
+                .take(n)
+                .collect::<Vec<i64>>();
+            let mut fenwick = Fenwick::from_slice(&a);
+
+            for _ in 0..QUERY_COUNT {
+                match rng.gen_range(0, 4) {
+                    0 => {
+                        let x = rng.gen_range(-100, 100);
+                        a.push(x);
+                        fenwick.push(x);
+                    }
+                    1 => assert_eq!(fenwick.pop(), a.pop()),
+                    2 if !a.is_empty() => {
+                        let i = gen_index(&mut rng, a.len());
+                        let x = rng.gen_range(-100, 100);
+                        a[i] += x;
+                        fenwick.add(i, x);
+                    }
+                    _ => {
+                        let i = rng.gen_range(0, a.len() + 1);
+                        assert_eq!(fenwick.prefix_sum(i), a[..i].iter().sum::<i64>());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fp() {
+        type Fp = fp::F998244353;
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let n = rng.gen_range(1, 20);
+            let mut a = iter::repeat_with(|| Fp::new(rng.gen_range(0, 998_244_353)))
+                .take(n)
+                .collect::<Vec<_>>();
+            let mut fenwick = Fenwick::from_slice(&a);
+
+            for _ in 0..QUERY_COUNT {
+                match rng.gen_range(0, 3) {
+                    0 => {
+                        let i = gen_index(&mut rng, n);
+                        let x = Fp::new(rng.gen_range(0, 998_244_353));
+                        a[i] += x;
+                        fenwick.add(i, x);
+                    }
+                    1 => {
+                        let i = gen_index(&mut rng, n);
+                        let x = Fp::new(rng.gen_range(0, 998_244_353));
+                        a[i] -= x;
+                        fenwick.sub(i, x);
+                    }
+                    _ => {
+                        let i = rng.gen_range(0, n + 1);
+                        assert_eq!(fenwick.prefix_sum(i), a[..i].iter().copied().sum::<Fp>());
+                    }
+                }
+            }
+            assert_eq!(fenwick.to_vec(), a);
+        }
+    }
 }
@@ -0,0 +1,204 @@
+use crate::lsb;
+use std::{cmp::Reverse, iter};
+
+/// 各点の値を増やす操作と、prefix の最大値のクエリができる Fenwick 木です。
+/// 値を減らす操作はできません。
+#[derive(Debug, Clone, PartialEq)]
+pub struct FenwickMax<T> {
+    table: Vec<T>,
+}
+impl<T: Ord + Clone> FenwickMax<T> {
+    /// `min` は単位元で、空な prefix の最大値として使われます。
+    pub fn new(len: usize, min: T) -> Self {
+        Self {
+            table: vec![min; len + 1],
+        }
+    }
+    pub fn from_slice(src: &[T], min: T) -> Self {
+        let mut table = iter::once(min)
+            .chain(src.iter().cloned())
+            .collect::<Vec<_>>();
+        let n = table.len();
+        for i in 1..n {
+            let j = i + lsb(i);
+            if j < n && table[j] < table[i] {
+                table[j] = table[i].clone();
+            }
+        }
+        Self { table }
+    }
+    pub fn len(&self) -> usize {
+        self.table.len() - 1
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// `a[i]` を `max(a[i], x)` で置き換えます。
+    pub fn chmax(&mut self, i: usize, x: T) {
+        let n = self.table.len();
+        iter::successors(Some(i + 1), |&i| Some(i + lsb(i)))
+            .take_while(|&i| i < n)
+            .for_each(|i| {
+                if self.table[i] < x {
+                    self.table[i] = x.clone();
+                }
+            })
+    }
+    pub fn prefix_max(&self, i: usize) -> T {
+        iter::successors(Some(i), |&i| Some(i - lsb(i)))
+            .take_while(|&i| i != 0)
+            .map(|i| &self.table[i])
+            .max()
+            .unwrap_or(&self.table[0])
+            .clone()
+    }
+    /// `prefix_max(j + 1) >= x` となる最小の `j` を返します。存在しなければ `len` を返します。
+    pub fn lower_bound(&self, x: &T) -> usize {
+        let mut d = self.table.len().next_power_of_two() / 2;
+        let mut j = 0;
+        while d != 0 {
+            if d + j < self.table.len() && &self.table[d + j] < x {
+                j += d;
+            }
+            d /= 2;
+        }
+        j
+    }
+}
+
+/// 各点の値を減らす操作と、prefix の最小値のクエリができる Fenwick 木です。
+/// 値を増やす操作はできません。
+#[derive(Debug, Clone, PartialEq)]
+pub struct FenwickMin<T> {
+    max: FenwickMax<Reverse<T>>,
+}
+impl<T: Ord + Clone> FenwickMin<T> {
+    /// `max` は単位元で、空な prefix の最小値として使われます。
+    pub fn new(len: usize, max: T) -> Self {
+        Self {
+            max: FenwickMax::new(len, Reverse(max)),
+        }
+    }
+    pub fn from_slice(src: &[T], max: T) -> Self {
+        Self {
+            max: FenwickMax::from_slice(
+                &src.iter().cloned().map(Reverse).collect::<Vec<_>>(),
+                Reverse(max),
+            ),
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.max.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.max.is_empty()
+    }
+    /// `a[i]` を `min(a[i], x)` で置き換えます。
+    pub fn chmin(&mut self, i: usize, x: T) {
+        self.max.chmax(i, Reverse(x));
+    }
+    pub fn prefix_min(&self, i: usize) -> T {
+        self.max.prefix_max(i).0
+    }
+    /// `prefix_min(j + 1) <= x` となる最小の `j` を返します。存在しなければ `len` を返します。
+    pub fn lower_bound(&self, x: &T) -> usize {
+        self.max.lower_bound(&Reverse(x.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    const TEST_COUNT: usize = 20;
+    const QUERY_COUNT: usize = 1000;
+    const VALUE_LIMIT: i64 = 100;
+
+    #[test]
+    fn test_max() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let n = rng.gen_range(1, 30);
+            let mut a = iter::repeat_with(|| rng.gen_range(-VALUE_LIMIT, VALUE_LIMIT))
+                .take(n)
+                .collect::<Vec<_>>();
+            let mut fenwick = FenwickMax::from_slice(&a, i64::MIN);
+
+            for _ in 0..QUERY_COUNT {
+                match rng.gen_range(0, 3) {
+                    0 => {
+                        let i = rng.gen_range(0, n);
+                        let x = rng.gen_range(-VALUE_LIMIT, VALUE_LIMIT);
+                        a[i] = a[i].max(x);
+                        fenwick.chmax(i, x);
+                    }
+                    1 => {
+                        let i = rng.gen_range(0, n + 1);
+                        let expected = a[..i].iter().copied().max().unwrap_or(i64::MIN);
+                        assert_eq!(fenwick.prefix_max(i), expected);
+                    }
+                    2 => {
+                        let x = rng.gen_range(-VALUE_LIMIT - 1, VALUE_LIMIT + 1);
+                        let expected = a.iter().position(|&y| x <= y).unwrap_or(n);
+                        assert_eq!(fenwick.lower_bound(&x), expected);
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            assert_eq!(fenwick, FenwickMax::from_slice(&a, i64::MIN));
+        }
+    }
+
+    #[test]
+    fn test_min() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let n = rng.gen_range(1, 30);
+            let mut a = iter::repeat_with(|| rng.gen_range(-VALUE_LIMIT, VALUE_LIMIT))
+                .take(n)
+                .collect::<Vec<_>>();
+            let mut fenwick = FenwickMin::new(n, i64::MAX);
+            a.iter().enumerate().for_each(|(i, &x)| fenwick.chmin(i, x));
+
+            for _ in 0..QUERY_COUNT {
+                match rng.gen_range(0, 3) {
+                    0 => {
+                        let i = rng.gen_range(0, n);
+                        let x = rng.gen_range(-VALUE_LIMIT, VALUE_LIMIT);
+                        a[i] = a[i].min(x);
+                        fenwick.chmin(i, x);
+                    }
+                    1 => {
+                        let i = rng.gen_range(0, n + 1);
+                        let expected = a[..i].iter().copied().min().unwrap_or(i64::MAX);
+                        assert_eq!(fenwick.prefix_min(i), expected);
+                    }
+                    2 => {
+                        let x = rng.gen_range(-VALUE_LIMIT - 1, VALUE_LIMIT + 1);
+                        let expected = a.iter().position(|&y| y <= x).unwrap_or(n);
+                        assert_eq!(fenwick.lower_bound(&x), expected);
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            assert_eq!(fenwick, FenwickMin::from_slice(&a, i64::MAX));
+        }
+    }
+
+    // 値を減らす更新はできません。`chmax` で小さな値を与えても何も起こりません。
+    #[test]
+    fn test_decreasing_update_is_ignored() {
+        let mut fenwick = FenwickMax::from_slice(&[3, 1, 4, 1, 5], 0);
+        fenwick.chmax(2, 2);
+        assert_eq!(fenwick.prefix_max(3), 4);
+        assert_eq!(fenwick, FenwickMax::from_slice(&[3, 1, 4, 1, 5], 0));
+
+        let mut fenwick = FenwickMin::from_slice(&[3, 1, 4, 1, 5], 9);
+        fenwick.chmin(1, 2);
+        assert_eq!(fenwick.prefix_min(2), 1);
+        assert_eq!(fenwick, FenwickMin::from_slice(&[3, 1, 4, 1, 5], 9));
+    }
+}
@@ -0,0 +1,94 @@
+use crate::lsb;
+use std::{fmt, iter};
+
+/// 可換モノイドの値を持ち、各点への作用と prefix の総積のクエリができる Fenwick 木です。
+///
+/// `op` は結合的かつ可換で、`identity` はその単位元である必要があります。
+/// 逆元は使わないので、区間の総積のクエリはできません。
+#[derive(Clone)]
+pub struct FenwickMonoid<T, F> {
+    table: Vec<T>,
+    op: F,
+}
+impl<T: Clone, F: Fn(&T, &T) -> T> FenwickMonoid<T, F> {
+    pub fn new(len: usize, identity: T, op: F) -> Self {
+        Self {
+            table: vec![identity; len + 1],
+            op,
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.table.len() - 1
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// `a[i]` を `op(a[i], x)` で置き換えます。
+    pub fn operate(&mut self, i: usize, x: T) {
+        let n = self.table.len();
+        iter::successors(Some(i + 1), |&i| Some(i + lsb(i)))
+            .take_while(|&i| i < n)
+            .for_each(|i| self.table[i] = (self.op)(&self.table[i], &x));
+    }
+    /// `a[0], ..., a[i - 1]` の総積を返します。
+    pub fn prefix(&self, i: usize) -> T {
+        iter::successors(Some(i), |&i| Some(i - lsb(i)))
+            .take_while(|&i| i != 0)
+            .fold(self.table[0].clone(), |acc, i| {
+                (self.op)(&acc, &self.table[i])
+            })
+    }
+}
+impl<T: fmt::Debug, F> fmt::Debug for FenwickMonoid<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FenwickMonoid")
+            .field("table", &self.table)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    const TEST_COUNT: usize = 20;
+    const QUERY_COUNT: usize = 1000;
+    const VALUE_LIMIT: u64 = 100;
+
+    fn gcd(x: u64, y: u64) -> u64 {
+        if y == 0 {
+            x
+        } else {
+            gcd(y, x % y)
+        }
+    }
+
+    #[test]
+    fn test_monoid() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let n = rng.gen_range(1, 30);
+            let mut a = vec![0; n];
+            let mut fenwick = FenwickMonoid::new(n, 0, |&x, &y| gcd(x, y));
+
+            for _ in 0..QUERY_COUNT {
+                match rng.gen_range(0, 2) {
+                    0 => {
+                        let i = rng.gen_range(0, n);
+                        let x = rng.gen_range(1, VALUE_LIMIT);
+                        a[i] = gcd(a[i], x);
+                        fenwick.operate(i, x);
+                    }
+                    1 => {
+                        let i = rng.gen_range(0, n + 1);
+                        let expected = a[..i].iter().fold(0, |acc, &x| gcd(acc, x));
+                        assert_eq!(fenwick.prefix(i), expected);
+                    }
+                    _ => unreachable!(),
+                }
+            }
+        }
+    }
+}
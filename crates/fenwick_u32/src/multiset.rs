@@ -0,0 +1,288 @@
+use crate::Fenwick;
+
+/// `0..n` の要素からなる多重集合です。
+#[derive(Debug, Clone, PartialEq)]
+pub struct FenwickMultiset {
+    fenwick: Fenwick,
+    len: usize,
+}
+impl FenwickMultiset {
+    pub fn new(n: usize) -> Self {
+        Self {
+            fenwick: Fenwick::from_slice(&vec![0; n]),
+            len: 0,
+        }
+    }
+    pub fn universe_len(&self) -> usize {
+        self.fenwick.len()
+    }
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    pub fn insert(&mut self, x: usize) {
+        assert!(
+            x < self.universe_len(),
+            "{} を挿入しようとしましたが、要素は {} 未満でなければなりません。",
+            x,
+            self.universe_len()
+        );
+        self.fenwick.add(x, 1);
+        self.len += 1;
+    }
+    /// `x` をひとつ削除します。`x` が含まれていなければ何もせず `false` を返します。
+    pub fn remove(&mut self, x: usize) -> bool {
+        if self.count(x) == 0 {
+            false
+        } else {
+            self.fenwick.sub(x, 1);
+            self.len -= 1;
+            true
+        }
+    }
+    /// `x` の個数を返します。`x` が `universe_len()` 以上のときには `0` です。
+    pub fn count(&self, x: usize) -> usize {
+        if self.universe_len() <= x {
+            return 0;
+        }
+        (self.fenwick.prefix_sum(x + 1) - self.fenwick.prefix_sum(x)) as usize
+    }
+    /// `x` 未満の要素の個数を返します。
+    pub fn rank(&self, x: usize) -> usize {
+        self.fenwick.prefix_sum(x.min(self.universe_len())) as usize
+    }
+    /// 小さい方から `k` 番目（0-indexed）の要素を返します。
+    pub fn nth(&self, k: usize) -> Option<usize> {
+        if k < self.len {
+            Some(self.fenwick.upper_bound(&(k as u32)))
+        } else {
+            None
+        }
+    }
+    /// `x` 以下の最大の要素を返します。
+    pub fn prev(&self, x: usize) -> Option<usize> {
+        self.rank(x.saturating_add(1))
+            .checked_sub(1)
+            .and_then(|k| self.nth(k))
+    }
+    /// `x` 以上の最小の要素を返します。
+    pub fn next(&self, x: usize) -> Option<usize> {
+        self.nth(self.rank(x))
+    }
+    /// 要素を昇順に、重複度の回数だけ返します。
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len).map(move |k| self.nth(k).unwrap())
+    }
+}
+
+/// 構築時に与えたキーの集合を座標圧縮して持つ `FenwickMultiset` です。
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompressedFenwickMultiset<T> {
+    keys: Vec<T>,
+    multiset: FenwickMultiset,
+}
+impl<T: Ord> CompressedFenwickMultiset<T> {
+    pub fn new(mut keys: Vec<T>) -> Self {
+        keys.sort();
+        keys.dedup();
+        let multiset = FenwickMultiset::new(keys.len());
+        Self { keys, multiset }
+    }
+    pub fn keys(&self) -> &[T] {
+        &self.keys
+    }
+    pub fn len(&self) -> usize {
+        self.multiset.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.multiset.is_empty()
+    }
+    pub fn insert(&mut self, x: &T) {
+        let i = self
+            .keys
+            .binary_search(x)
+            .expect("キーの集合に含まれていない要素を挿入しようとしています。");
+        self.multiset.insert(i);
+    }
+    pub fn remove(&mut self, x: &T) -> bool {
+        match self.keys.binary_search(x) {
+            Ok(i) => self.multiset.remove(i),
+            Err(_) => false,
+        }
+    }
+    pub fn count(&self, x: &T) -> usize {
+        match self.keys.binary_search(x) {
+            Ok(i) => self.multiset.count(i),
+            Err(_) => 0,
+        }
+    }
+    pub fn rank(&self, x: &T) -> usize {
+        self.multiset.rank(self.keys.partition_point(|k| k < x))
+    }
+    pub fn nth(&self, k: usize) -> Option<&T> {
+        self.multiset.nth(k).map(|i| &self.keys[i])
+    }
+    pub fn prev(&self, x: &T) -> Option<&T> {
+        self.keys
+            .partition_point(|k| k <= x)
+            .checked_sub(1)
+            .and_then(|i| self.multiset.prev(i))
+            .map(|i| &self.keys[i])
+    }
+    pub fn next(&self, x: &T) -> Option<&T> {
+        self.multiset
+            .next(self.keys.partition_point(|k| k < x))
+            .map(|i| &self.keys[i])
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.multiset.iter().map(move |i| &self.keys[i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+    use std::collections::BTreeMap;
+
+    const TEST_COUNT: usize = 20;
+    const QUERY_COUNT: usize = 1000;
+
+    fn brute_vec(brute: &BTreeMap<i64, usize>) -> Vec<i64> {
+        brute
+            .iter()
+            .flat_map(|(&x, &c)| std::iter::repeat_n(x, c))
+            .collect()
+    }
+
+    #[test]
+    fn test_multiset() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let n = rng.gen_range(1, 20);
+            let mut multiset = FenwickMultiset::new(n);
+            let mut brute = vec![0; n];
+
+            for _ in 0..QUERY_COUNT {
+                let x = rng.gen_range(0, n);
+                let sorted = (0..n)
+                    .flat_map(|x| std::iter::repeat_n(x, brute[x]))
+                    .collect::<Vec<_>>();
+                match rng.gen_range(0, 7) {
+                    0 => {
+                        multiset.insert(x);
+                        brute[x] += 1;
+                    }
+                    1 => {
+                        let expected = brute[x] != 0;
+                        if expected {
+                            brute[x] -= 1;
+                        }
+                        assert_eq!(multiset.remove(x), expected);
+                    }
+                    2 => assert_eq!(multiset.count(x), brute[x]),
+                    3 => {
+                        let x = rng.gen_range(0, n + 2);
+                        let expected = sorted.iter().filter(|&&y| y < x).count();
+                        assert_eq!(multiset.rank(x), expected);
+                    }
+                    4 => {
+                        let k = rng.gen_range(0, sorted.len() + 2);
+                        assert_eq!(multiset.nth(k), sorted.get(k).copied());
+                    }
+                    5 => {
+                        let x = rng.gen_range(0, n + 2);
+                        let expected = sorted.iter().rev().find(|&&y| y <= x).copied();
+                        assert_eq!(multiset.prev(x), expected);
+                    }
+                    6 => {
+                        let x = rng.gen_range(0, n + 2);
+                        let expected = sorted.iter().find(|&&y| x <= y).copied();
+                        assert_eq!(multiset.next(x), expected);
+                    }
+                    _ => unreachable!(),
+                }
+                assert_eq!(multiset.len(), brute.iter().sum::<usize>());
+            }
+            let sorted = (0..n)
+                .flat_map(|x| std::iter::repeat_n(x, brute[x]))
+                .collect::<Vec<_>>();
+            assert_eq!(multiset.iter().collect::<Vec<_>>(), sorted);
+        }
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let mut multiset = FenwickMultiset::new(3);
+        multiset.insert(1);
+        assert_eq!(multiset.count(3), 0);
+        assert_eq!(multiset.count(usize::MAX), 0);
+        assert!(!multiset.remove(3));
+        assert_eq!(multiset.iter().collect::<Vec<_>>(), vec![1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_insert_out_of_range() {
+        let mut multiset = FenwickMultiset::new(3);
+        multiset.insert(5);
+    }
+
+    #[test]
+    fn test_compressed_multiset() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let n = rng.gen_range(1, 20);
+            let keys = std::iter::repeat_with(|| rng.gen_range(-1_000_000_000, 1_000_000_000))
+                .take(n)
+                .collect::<Vec<i64>>();
+            let mut multiset = CompressedFenwickMultiset::new(keys.clone());
+            let mut brute = BTreeMap::new();
+
+            for _ in 0..QUERY_COUNT {
+                let x = *keys.choose(&mut rng).unwrap();
+                let y = x + rng.gen_range(-1, 2);
+                let sorted = brute_vec(&brute);
+                match rng.gen_range(0, 7) {
+                    0 => {
+                        multiset.insert(&x);
+                        *brute.entry(x).or_insert(0) += 1;
+                    }
+                    1 => {
+                        let expected = brute.get(&y).is_some_and(|&c| c != 0);
+                        if expected {
+                            *brute.get_mut(&y).unwrap() -= 1;
+                        }
+                        assert_eq!(multiset.remove(&y), expected);
+                    }
+                    2 => assert_eq!(multiset.count(&y), brute.get(&y).copied().unwrap_or(0)),
+                    3 => {
+                        let expected = sorted.iter().filter(|&&z| z < y).count();
+                        assert_eq!(multiset.rank(&y), expected);
+                    }
+                    4 => {
+                        let k = rng.gen_range(0, sorted.len() + 2);
+                        assert_eq!(multiset.nth(k), sorted.get(k));
+                    }
+                    5 => {
+                        let expected = sorted.iter().rev().find(|&&z| z <= y);
+                        assert_eq!(multiset.prev(&y), expected);
+                    }
+                    6 => {
+                        let expected = sorted.iter().find(|&&z| y <= z);
+                        assert_eq!(multiset.next(&y), expected);
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            assert_eq!(
+                multiset.iter().copied().collect::<Vec<_>>(),
+                brute_vec(&brute)
+            );
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{lsb, Fenwick, Group};
+use rayon::prelude::*;
+
+const CHUNK_LEN: usize = 1 << 16;
+
+impl<T: Group + Send + Sync> Fenwick<T> {
+    /// `from_slice` と同じ表を並列に構築します。
+    pub fn from_slice_par(src: &[T]) -> Self {
+        from_slice_par_with_chunk_len(src, CHUNK_LEN)
+    }
+}
+
+// 長さ `chunk_len`（2 べき）のチャンクごとに独立に Fenwick 木を作り、
+// チャンクの境界にある、チャンクをまたぐセルだけをチャンクの和の Fenwick 木から埋めます。
+fn from_slice_par_with_chunk_len<T: Group + Send + Sync>(
+    src: &[T],
+    chunk_len: usize,
+) -> Fenwick<T> {
+    debug_assert!(chunk_len.is_power_of_two());
+    let mut table = vec![T::zero(); src.len() + 1];
+    table[1..]
+        .par_chunks_mut(chunk_len)
+        .zip(src.par_chunks(chunk_len))
+        .for_each(|(dst, src)| {
+            dst.copy_from_slice(src);
+            let n = dst.len();
+            for i in 1..=n {
+                let j = i + lsb(i);
+                if j <= n {
+                    dst[j - 1] = dst[j - 1].add(dst[i - 1]);
+                }
+            }
+        });
+    let chunk_sums = (1..=src.len() / chunk_len)
+        .map(|k| table[k * chunk_len])
+        .collect::<Vec<_>>();
+    let chunk_fenwick = Fenwick::from_slice(&chunk_sums);
+    for (k, &x) in chunk_fenwick.table.iter().enumerate().skip(1) {
+        table[k * chunk_len] = x;
+    }
+    Fenwick { table }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+    use std::iter;
+
+    #[test]
+    fn test_from_slice_par() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..200 {
+            let n = rng.gen_range(0, 100);
+            let a = iter::repeat_with(|| rng.gen_range(0, 100))
+                .take(n)
+                .collect::<Vec<u32>>();
+            let expected = Fenwick::from_slice(&a);
+            for &chunk_len in &[1, 2, 4, 8, 16, 128] {
+                let result = from_slice_par_with_chunk_len(&a, chunk_len);
+                assert_eq!(result, expected);
+            }
+        }
+
+        let a = iter::repeat_with(|| rng.gen_range(0, 100))
+            .take(3 * CHUNK_LEN + 12345)
+            .collect::<Vec<u32>>();
+        assert_eq!(Fenwick::from_slice_par(&a), Fenwick::from_slice(&a));
+    }
+
+    #[test]
+    fn test_prefix_sums() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+        let a = iter::repeat_with(|| rng.gen_range(0, 100))
+            .take(1000)
+            .collect::<Vec<u32>>();
+        let fenwick = Fenwick::from_slice_par(&a);
+        let indices = iter::repeat_with(|| rng.gen_range(0, a.len() + 1))
+            .take(1000)
+            .collect::<Vec<_>>();
+        let expected = indices
+            .iter()
+            .map(|&i| a[..i].iter().sum::<u32>())
+            .collect::<Vec<_>>();
+        assert_eq!(fenwick.prefix_sums(&indices), expected);
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{lsb, Fenwick};
+use std::iter;
+
+/// 過去の任意の版の prefix sum を求められる Fenwick 木です。
+/// 各セルが `(版, 値)` の履歴を持つので、`add` 一回あたりのメモリは O(log n) です。
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersistentFenwick {
+    history: Vec<Vec<(usize, u32)>>,
+    version: usize,
+}
+impl PersistentFenwick {
+    /// 版 `0` が初期状態です。
+    pub fn from_slice(src: &[u32]) -> Self {
+        Self {
+            history: Fenwick::from_slice(src)
+                .table
+                .into_iter()
+                .map(|x| vec![(0, x)])
+                .collect(),
+            version: 0,
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.history.len() - 1
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// 最新の版を返します。
+    pub fn version(&self) -> usize {
+        self.version
+    }
+    /// 最新の版に `a[i] += x` をした新しい版を作り、その版を返します。
+    pub fn add(&mut self, i: usize, x: u32) -> usize {
+        assert!(
+            i < self.len(),
+            "{} 番目に足そうとしましたが、長さは {} です。",
+            i,
+            self.len()
+        );
+        self.version += 1;
+        let version = self.version;
+        let n = self.history.len();
+        iter::successors(Some(i + 1), |&i| Some(i + lsb(i)))
+            .take_while(|&i| i < n)
+            .for_each(|i| {
+                let cell = &mut self.history[i];
+                let last = cell.last().unwrap().1;
+                cell.push((version, last + x));
+            });
+        version
+    }
+    pub fn prefix_sum(&self, i: usize) -> u32 {
+        self.prefix_sum_at(self.version, i)
+    }
+    pub fn prefix_sum_at(&self, version: usize, i: usize) -> u32 {
+        assert!(version <= self.version, "まだ作られていない版です。");
+        iter::successors(Some(i), |&i| Some(i - lsb(i)))
+            .take_while(|&i| i != 0)
+            .map(|i| {
+                let cell = &self.history[i];
+                cell[cell.partition_point(|&(v, _)| v <= version) - 1].1
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    const TEST_COUNT: usize = 20;
+    const QUERY_COUNT: usize = 1000;
+    const VALUE_LIMIT: u32 = 100;
+
+    #[test]
+    fn test_persistent() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let n = rng.gen_range(1, 20);
+            let a = iter::repeat_with(|| rng.gen_range(0, VALUE_LIMIT))
+                .take(n)
+                .collect::<Vec<_>>();
+            let mut fenwick = PersistentFenwick::from_slice(&a);
+            let mut versions = vec![a];
+
+            for _ in 0..QUERY_COUNT {
+                match rng.gen_range(0, 2) {
+                    0 => {
+                        let i = rng.gen_range(0, n);
+                        let x = rng.gen_range(0, VALUE_LIMIT);
+                        let mut a = versions.last().unwrap().clone();
+                        a[i] += x;
+                        versions.push(a);
+                        assert_eq!(fenwick.add(i, x), versions.len() - 1);
+                    }
+                    1 => {
+                        let version = rng.gen_range(0, versions.len());
+                        let i = rng.gen_range(0, n + 1);
+                        let expected = versions[version][..i].iter().sum::<u32>();
+                        assert_eq!(fenwick.prefix_sum_at(version, i), expected);
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            let expected = versions.last().unwrap().iter().sum::<u32>();
+            assert_eq!(fenwick.prefix_sum(n), expected);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_add_out_of_range() {
+        let mut fenwick = PersistentFenwick::from_slice(&[1, 2, 3]);
+        fenwick.add(3, 1);
+    }
+}
@@ -0,0 +1,168 @@
+use std::{collections::HashMap, iter};
+
+/// 添字の範囲が `0..len` の、表をハッシュマップで持つ Fenwick 木です。
+/// 値の入っていないセルは持たないので、`len` が 1e18 程度でも使えます。
+#[derive(Debug, Clone)]
+pub struct SparseFenwick {
+    len: u64,
+    table: HashMap<u64, u32>,
+}
+impl SparseFenwick {
+    pub fn new(len: u64) -> Self {
+        Self {
+            len,
+            table: HashMap::new(),
+        }
+    }
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    pub fn prefix_sum(&self, i: u64) -> u32 {
+        iter::successors(Some(i), |&i| Some(i - lsb(i)))
+            .take_while(|&i| i != 0)
+            .filter_map(|i| self.table.get(&i))
+            .sum()
+    }
+    pub fn add(&mut self, i: u64, x: u32) {
+        assert!(i < self.len);
+        let n = self.len;
+        iter::successors(Some(i + 1), |&i| i.checked_add(lsb(i)))
+            .take_while(|&i| i <= n)
+            .for_each(|i| *self.table.entry(i).or_insert(0) += x)
+    }
+    pub fn sub(&mut self, i: u64, x: u32) {
+        assert!(i < self.len);
+        let n = self.len;
+        iter::successors(Some(i + 1), |&i| i.checked_add(lsb(i)))
+            .take_while(|&i| i <= n)
+            .for_each(|i| *self.table.entry(i).or_insert(0) -= x)
+    }
+    pub fn upper_bound(&self, x: &u32) -> u64 {
+        let mut d = if self.len == 0 {
+            0
+        } else {
+            1 << (63 - self.len.leading_zeros())
+        };
+        let mut j = 0;
+        let mut now = 0;
+        while d != 0 {
+            if d + j <= self.len {
+                let next = now + self.table.get(&(d + j)).copied().unwrap_or(0);
+                if &next <= x {
+                    now = next;
+                    j += d;
+                }
+            }
+            d /= 2;
+        }
+        j
+    }
+}
+#[inline]
+fn lsb(i: u64) -> u64 {
+    i & i.wrapping_neg()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Fenwick;
+    use rand::prelude::*;
+    use std::collections::BTreeMap;
+
+    const TEST_COUNT: usize = 20;
+    const QUERY_COUNT: usize = 1000;
+    const VALUE_LIMIT: u32 = 100;
+
+    #[test]
+    fn test_agree_with_dense() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for _ in 0..TEST_COUNT {
+            let n = rng.gen_range(1, 40);
+            let mut fenwick = Fenwick::from_slice(&vec![0; n]);
+            let mut sparse = SparseFenwick::new(n as u64);
+
+            for _ in 0..QUERY_COUNT {
+                match rng.gen_range(0, 3) {
+                    0 => {
+                        let i = rng.gen_range(0, n);
+                        let x = rng.gen_range(0, VALUE_LIMIT);
+                        fenwick.add(i, x);
+                        sparse.add(i as u64, x);
+                    }
+                    1 => {
+                        let i = rng.gen_range(0, n + 1);
+                        assert_eq!(sparse.prefix_sum(i as u64), fenwick.prefix_sum(i));
+                    }
+                    2 => {
+                        let x = rng.gen_range(0, fenwick.prefix_sum(n) + 2);
+                        assert_eq!(sparse.upper_bound(&x), fenwick.upper_bound(&x) as u64);
+                    }
+                    _ => unreachable!(),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sub_zero() {
+        let mut sparse = SparseFenwick::new(10);
+        sparse.sub(3, 0);
+        assert_eq!(sparse.prefix_sum(10), 0);
+        sparse.add(3, 5);
+        sparse.sub(3, 2);
+        assert_eq!(sparse.prefix_sum(3), 0);
+        assert_eq!(sparse.prefix_sum(4), 3);
+    }
+
+    #[test]
+    fn test_large_keys() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+
+        for &n in &[1_000_000_000, 1 << 62, u64::MAX] {
+            let mut sparse = SparseFenwick::new(n);
+            let mut brute = BTreeMap::<u64, u32>::new();
+
+            for _ in 0..QUERY_COUNT {
+                match rng.gen_range(0, 4) {
+                    0 => {
+                        let i = rng.gen_range(0, n);
+                        let x = rng.gen_range(0, VALUE_LIMIT);
+                        sparse.add(i, x);
+                        *brute.entry(i).or_insert(0) += x;
+                    }
+                    1 => {
+                        if let Some((&i, &y)) = brute.iter().choose(&mut rng) {
+                            let x = rng.gen_range(0, y + 1);
+                            sparse.sub(i, x);
+                            *brute.get_mut(&i).unwrap() -= x;
+                        }
+                    }
+                    2 => {
+                        let i = rng.gen_range(0, n);
+                        let expected = brute.range(..i).map(|(_, &x)| x).sum::<u32>();
+                        assert_eq!(sparse.prefix_sum(i), expected);
+                    }
+                    3 => {
+                        let total = brute.values().sum::<u32>();
+                        let x = rng.gen_range(0, total + 2);
+                        let mut now = 0;
+                        let expected = brute
+                            .iter()
+                            .find(|&(_, &y)| {
+                                now += y;
+                                x < now
+                            })
+                            .map_or(n, |(&i, _)| i);
+                        assert_eq!(sparse.upper_bound(&x), expected);
+                    }
+                    _ => unreachable!(),
+                }
+            }
+        }
+    }
+}
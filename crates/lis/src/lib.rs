@@ -0,0 +1,199 @@
+use fenwick_u32::{compress, FenwickMax, FenwickMonoid};
+use std::{cmp::Ordering, iter};
+
+type Fp = fp::F998244353;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Strictness {
+    /// `a[i] < a[j]` を要求します。
+    Strict,
+    /// `a[i] <= a[j]` を要求します。
+    NonStrict,
+}
+
+pub fn lis_length<T: Ord>(a: &[T], strictness: Strictness) -> usize {
+    let (a, m) = compress(a);
+    let mut fenwick = FenwickMax::new(m, 0);
+    for &x in &a {
+        let len = fenwick.prefix_max(query_end(x, strictness)) + 1;
+        fenwick.chmax(x, len);
+    }
+    fenwick.prefix_max(m)
+}
+
+/// 最長増加部分列のひとつを、添字の昇順で返します。
+pub fn lis_indices<T: Ord>(a: &[T], strictness: Strictness) -> Vec<usize> {
+    let (a, m) = compress(a);
+    let mut fenwick = FenwickMax::new(m, None);
+    let mut prev = vec![None; a.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let best = fenwick.prefix_max(query_end(x, strictness));
+        prev[i] = best.map(|(_, j)| j);
+        let len = best.map_or(0, |(len, _)| len) + 1;
+        fenwick.chmax(x, Some((len, i)));
+    }
+    let mut res =
+        iter::successors(fenwick.prefix_max(m).map(|(_, i)| i), |&i| prev[i]).collect::<Vec<_>>();
+    res.reverse();
+    res
+}
+
+/// 最長増加部分列の（添字の列としての）個数を返します。空列の場合は空列ひとつと数えます。
+pub fn count_lis<T: Ord>(a: &[T], strictness: Strictness) -> Fp {
+    let (a, m) = compress(a);
+    let mut fenwick = FenwickMonoid::new(m, (0, Fp::new(0)), merge);
+    for &x in &a {
+        let (len, count) = fenwick.prefix(query_end(x, strictness));
+        let count = if len == 0 { Fp::new(1) } else { count };
+        fenwick.operate(x, (len + 1, count));
+    }
+    match fenwick.prefix(m) {
+        (0, _) => Fp::new(1),
+        (_, count) => count,
+    }
+}
+
+/// 重み `w[i]` の和が最大となる増加部分列の、重みの和を返します。
+pub fn weighted_lis<T: Ord>(a: &[T], w: &[u64], strictness: Strictness) -> u64 {
+    assert_eq!(a.len(), w.len(), "長さが違います。");
+    let (a, m) = compress(a);
+    let mut fenwick = FenwickMax::new(m, 0);
+    for (&x, &w) in a.iter().zip(w) {
+        let sum = fenwick.prefix_max(query_end(x, strictness)) + w;
+        fenwick.chmax(x, sum);
+    }
+    fenwick.prefix_max(m)
+}
+
+fn query_end(x: usize, strictness: Strictness) -> usize {
+    match strictness {
+        Strictness::Strict => x,
+        Strictness::NonStrict => x + 1,
+    }
+}
+
+// 長さの最大値と、それを達成する個数を合わせる演算です。
+fn merge(&x: &(usize, Fp), &y: &(usize, Fp)) -> (usize, Fp) {
+    match x.0.cmp(&y.0) {
+        Ordering::Less => y,
+        Ordering::Greater => x,
+        Ordering::Equal => (x.0, x.1 + y.1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    const TEST_COUNT: usize = 300;
+    const STRICTNESSES: [Strictness; 2] = [Strictness::Strict, Strictness::NonStrict];
+
+    fn gen_vec(rng: &mut StdRng) -> Vec<i32> {
+        let n = rng.gen_range(0, 20);
+        let lim = rng.gen_range(1, 20);
+        iter::repeat_with(|| rng.gen_range(0, lim))
+            .take(n)
+            .collect()
+    }
+
+    fn is_increasing(x: i32, y: i32, strictness: Strictness) -> bool {
+        match strictness {
+            Strictness::Strict => x < y,
+            Strictness::NonStrict => x <= y,
+        }
+    }
+
+    // 各 `i` について、`i` で終わる増加部分列の (最長の長さ, その個数, 重みの和の最大値) です。
+    fn brute(a: &[i32], w: &[u64], strictness: Strictness) -> Vec<(usize, u64, u64)> {
+        let mut dp = Vec::<(usize, u64, u64)>::new();
+        for i in 0..a.len() {
+            let mut now = (1, 1, w[i]);
+            for j in (0..i).filter(|&j| is_increasing(a[j], a[i], strictness)) {
+                match (dp[j].0 + 1).cmp(&now.0) {
+                    Ordering::Greater => now = (dp[j].0 + 1, dp[j].1, now.2),
+                    Ordering::Equal => now.1 += dp[j].1,
+                    Ordering::Less => {}
+                }
+                now.2 = now.2.max(dp[j].2 + w[i]);
+            }
+            dp.push(now);
+        }
+        dp
+    }
+
+    #[test]
+    fn test_lis_length() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+        for _ in 0..TEST_COUNT {
+            let a = gen_vec(&mut rng);
+            for &strictness in &STRICTNESSES {
+                let expected = brute(&a, &vec![0; a.len()], strictness)
+                    .iter()
+                    .map(|&(len, _, _)| len)
+                    .max()
+                    .unwrap_or(0);
+                assert_eq!(lis_length(&a, strictness), expected, "a = {:?}", &a);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lis_indices() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+        for _ in 0..TEST_COUNT {
+            let a = gen_vec(&mut rng);
+            for &strictness in &STRICTNESSES {
+                let result = lis_indices(&a, strictness);
+                assert_eq!(result.len(), lis_length(&a, strictness));
+                assert!(result.windows(2).all(|v| v[0] < v[1]));
+                assert!(result
+                    .windows(2)
+                    .all(|v| is_increasing(a[v[0]], a[v[1]], strictness)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_count_lis() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+        for _ in 0..TEST_COUNT {
+            let a = gen_vec(&mut rng);
+            for &strictness in &STRICTNESSES {
+                let dp = brute(&a, &vec![0; a.len()], strictness);
+                let max = dp.iter().map(|&(len, _, _)| len).max().unwrap_or(0);
+                let expected = dp
+                    .iter()
+                    .filter(|&&(len, _, _)| len == max)
+                    .map(|&(_, count, _)| count)
+                    .sum::<u64>();
+                let expected = if a.is_empty() { 1 } else { expected };
+                assert_eq!(
+                    count_lis(&a, strictness),
+                    Fp::new(expected as i64),
+                    "a = {:?}",
+                    &a
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_weighted_lis() {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(42);
+        for _ in 0..TEST_COUNT {
+            let a = gen_vec(&mut rng);
+            let w = iter::repeat_with(|| rng.gen_range(0, 100))
+                .take(a.len())
+                .collect::<Vec<u64>>();
+            for &strictness in &STRICTNESSES {
+                let expected = brute(&a, &w, strictness)
+                    .iter()
+                    .map(|&(_, _, sum)| sum)
+                    .max()
+                    .unwrap_or(0);
+                assert_eq!(weighted_lis(&a, &w, strictness), expected, "a = {:?}", &a);
+            }
+        }
+    }
+}